@@ -1,3 +1,4 @@
+use crate::config::authority;
 use crate::types::{Result, EMPTY_YAML_VEC};
 use oauth2::{AuthUrl, ClientId, ClientSecret, RedirectUrl, Scope, TokenUrl};
 use yaml_rust::Yaml;
@@ -47,7 +48,8 @@ impl OAuth2Info {
             .filter(|y| y.is_some())
             .map(|y| Scope::new(y.unwrap().to_string()))
             .collect();
-        let redirect_url = RedirectUrl::new(format!("http://{}:{}/authRedirect", host, port))?;
+        let redirect_url =
+            RedirectUrl::new(format!("http://{}/authRedirect", authority(host, port)))?;
         Ok(Box::new(Self {
             auth_url,
             token_url,
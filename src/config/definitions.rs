@@ -4,6 +4,8 @@ pub const DB_NAME: &str = "bld-server.db";
 pub const PUSH: &str = "push";
 pub const GET: &str = "get";
 pub const VAR_TOKEN: &str = "bld:var:";
+pub const VAR_CHECKOUT_SHA: &str = "BLD_CHECKOUT_SHA";
+pub const SYSTEM_USER: &str = "system";
 
 pub const TOOL_DEFAULT_PIPELINE: &str = "default";
 pub const TOOL_DEFAULT_PIPELINE_FILE: &str = "default.yaml";
@@ -15,7 +17,27 @@ pub const LOCAL_SERVER_HOST: &str = "127.0.0.1";
 pub const LOCAL_SERVER_PORT: i64 = 6080;
 pub const LOCAL_LOGS: &str = ".bld/logs";
 pub const LOCAL_DB: &str = ".bld/db";
-pub const LOCAL_DOCKER_URL: &str = "tcp://127.0.0.1:2376";
+pub const LOCAL_DOCKER_URL: &str = "unix:///var/run/docker.sock";
+pub const LOCAL_STOP_GRACE_PERIOD: u64 = 10;
+pub const LOCAL_MAX_CONCURRENT_RUNS: usize = 4;
+pub const LOCAL_RECORD_RUNS: bool = false;
+pub const LOCAL_SERVER_RUNS: bool = true;
+pub const LOCAL_LEASE_DURATION: u64 = 15;
+pub const SERVER_LEASE_NAME: &str = "leader";
+pub const MONIT_RECONNECT_ATTEMPTS: usize = 5;
+pub const MONIT_RECONNECT_DELAY: u64 = 2;
+pub const LOG_HUB_CAPACITY: usize = 1024;
+pub const LOCAL_LOGGING_RING_SIZE: usize = 100;
+pub const LOCAL_LOGGING_SYSLOG_SOCKET: &str = "/dev/log";
+pub const SEARCH_MAX_RESULTS: usize = 1000;
+pub const TRIGGER_MAX_DEPTH: usize = 16;
+pub const LOGS_CHUNK_SIZE: usize = 65536;
+pub const LOGS_TAIL_LINE_SIZE: u64 = 256;
+pub const WORKER_RECONNECT_MIN_DELAY: u64 = 1;
+pub const WORKER_RECONNECT_MAX_DELAY: u64 = 60;
+pub const LOCAL_RETENTION_INTERVAL: u64 = 3600;
+pub const DOCKER_HUB_REGISTRY: &str = "docker.io";
+pub const HOOK_SIGNATURE_HEADER: &str = "X-Hub-Signature-256";
 pub const LOCAL_MACHINE_TMP_DIR: &str = ".bld/tmp";
 pub const REMOTE_SERVER_NAME: &str = "demo_server";
 pub const REMOTE_SERVER_HOST: &str = "127.0.0.1";
@@ -37,9 +59,8 @@ pub fn default_server_config() -> String {
     host: {}
     port: {}
     logs: {}
-    db: {}
-    docker-url: {}",
-        true, LOCAL_SERVER_HOST, LOCAL_SERVER_PORT, LOCAL_LOGS, LOCAL_DB, LOCAL_DOCKER_URL
+    db: {}",
+        true, LOCAL_SERVER_HOST, LOCAL_SERVER_PORT, LOCAL_LOGS, LOCAL_DB
     )
 }
 
@@ -47,15 +68,10 @@ pub fn default_client_config() -> String {
     format!(
         r"local:
     server-mode: {} 
-    docker-url: {}
 remote:
     - server: {}
       host: {}
       port: {}",
-        LOCAL_SERVER_MODE,
-        LOCAL_DOCKER_URL,
-        REMOTE_SERVER_NAME,
-        REMOTE_SERVER_HOST,
-        REMOTE_SERVER_PORT
+        LOCAL_SERVER_MODE, REMOTE_SERVER_NAME, REMOTE_SERVER_HOST, REMOTE_SERVER_PORT
     )
 }
@@ -0,0 +1,137 @@
+use crate::path;
+use crate::types::Result;
+use std::fs;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+use yaml_rust::Yaml;
+
+#[derive(Debug)]
+pub struct BldDockerTlsConfig {
+    pub ca: Option<String>,
+    pub cert: String,
+    pub key: String,
+    pub verify: bool,
+}
+
+impl BldDockerTlsConfig {
+    fn from_cert_path(cert_path: &str, verify: bool) -> Self {
+        let file = |name: &str| path![cert_path, name].display().to_string();
+        Self {
+            ca: Some(file("ca.pem")),
+            cert: file("cert.pem"),
+            key: file("key.pem"),
+            verify,
+        }
+    }
+
+    pub fn load(yaml: &Yaml) -> Result<Option<Self>> {
+        if yaml.is_badvalue() {
+            return Ok(None);
+        }
+        let verify = yaml["verify"].as_bool().or(Some(true)).unwrap();
+        if let Some(cert_path) = yaml["cert-path"].as_str() {
+            return Ok(Some(Self::from_cert_path(cert_path, verify)));
+        }
+        let ca = yaml["ca"].as_str().map(|c| c.to_string());
+        let cert = yaml["cert"]
+            .as_str()
+            .ok_or("docker-tls must define a cert or a cert-path")?
+            .to_string();
+        let key = yaml["key"]
+            .as_str()
+            .ok_or("docker-tls must define a key or a cert-path")?
+            .to_string();
+        if verify && ca.is_none() {
+            return Err("docker-tls must define a ca when verify is enabled".into());
+        }
+        Ok(Some(Self {
+            ca,
+            cert,
+            key,
+            verify,
+        }))
+    }
+
+    pub fn from_env() -> Option<Self> {
+        let cert_path = std::env::var("DOCKER_CERT_PATH").ok()?;
+        let verify = std::env::var("DOCKER_TLS_VERIFY")
+            .map(|v| !v.is_empty() && v != "0")
+            .unwrap_or(false);
+        Some(Self::from_cert_path(&cert_path, verify))
+    }
+
+    fn is_in_dir(file: &str, dir: &Path, name: &str) -> bool {
+        Path::new(file) == path![dir, name].as_path()
+    }
+
+    #[cfg(unix)]
+    fn create_private_dir(dir: &Path) -> Result<()> {
+        use std::os::unix::fs::DirBuilderExt;
+        fs::DirBuilder::new().mode(0o700).create(dir)?;
+        Ok(())
+    }
+
+    #[cfg(not(unix))]
+    fn create_private_dir(dir: &Path) -> Result<()> {
+        fs::create_dir(dir)?;
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    fn stage_key(from: &str, to: &Path) -> Result<()> {
+        use std::io::Write;
+        use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+        let content = fs::read(from)?;
+        let mut file = fs::OpenOptions::new()
+            .create_new(true)
+            .write(true)
+            .mode(0o600)
+            .open(to)?;
+        file.set_permissions(fs::Permissions::from_mode(0o600))?;
+        file.write_all(&content)?;
+        Ok(())
+    }
+
+    #[cfg(not(unix))]
+    fn stage_key(from: &str, to: &Path) -> Result<()> {
+        fs::copy(from, to)?;
+        Ok(())
+    }
+
+    pub fn apply_env(&self) -> Result<()> {
+        std::env::set_var("DOCKER_CERT_PATH", self.cert_path()?);
+        if self.verify {
+            std::env::set_var("DOCKER_TLS_VERIFY", "1");
+        } else {
+            std::env::remove_var("DOCKER_TLS_VERIFY");
+        }
+        Ok(())
+    }
+
+    pub fn cert_path(&self) -> Result<String> {
+        let dir = Path::new(&self.cert)
+            .parent()
+            .unwrap_or_else(|| Path::new(""));
+        let ca_in_dir = match &self.ca {
+            Some(ca) if self.verify => Self::is_in_dir(ca, dir, "ca.pem"),
+            _ => true,
+        };
+        if ca_in_dir
+            && Self::is_in_dir(&self.cert, dir, "cert.pem")
+            && Self::is_in_dir(&self.key, dir, "key.pem")
+        {
+            return Ok(dir.display().to_string());
+        }
+        let staging = path![
+            std::env::temp_dir(),
+            format!("bld-docker-tls-{}", Uuid::new_v4())
+        ];
+        Self::create_private_dir(&staging)?;
+        if let (Some(ca), true) = (&self.ca, self.verify) {
+            fs::copy(ca, path![&staging, "ca.pem"])?;
+        }
+        fs::copy(&self.cert, path![&staging, "cert.pem"])?;
+        Self::stage_key(&self.key, &path![&staging, "key.pem"])?;
+        Ok(staging.display().to_string())
+    }
+}
@@ -1,4 +1,4 @@
-use crate::config::{Auth, AuthValidation, BldConfig, BldLocalConfig, BldRemoteConfig};
+use crate::config::{Auth, AuthValidation, BldConfig, BldLocalConfig, BldRemoteConfig, LogSink};
 use crate::helpers::term;
 use crate::types::Result;
 use clap::ArgMatches;
@@ -22,7 +22,91 @@ fn list_locals(local: &BldLocalConfig) -> Result<()> {
     println!("- port: {}", local.port);
     println!("- logs: {}", local.logs);
     println!("- db: {}", local.db);
+    println!("- max-concurrent-runs: {}", local.max_concurrent_runs);
+    println!("- server-runs: {}", local.server_runs);
+    println!("- stop-grace-period: {}", local.stop_grace_period);
+    println!("- record-runs: {}", local.record_runs);
     println!("- docker-url: {}", local.docker_url);
+    if let Some(tls) = &local.docker_tls {
+        println!("- docker-tls:");
+        if let Some(ca) = &tls.ca {
+            println!("  - ca: {}", ca);
+        }
+        println!("  - cert: {}", tls.cert);
+        println!("  - key: {}", tls.key);
+        println!("  - verify: {}", tls.verify);
+    }
+    if !local.registries.is_empty() {
+        println!("- registries:");
+        for registry in local.registries.iter() {
+            println!("  - url: {}", registry.url);
+            println!("    username: {}", registry.username);
+            println!("    password: ***********");
+        }
+    }
+    if !local.schedules.is_empty() {
+        println!("- schedules:");
+        for schedule in local.schedules.iter() {
+            println!("  - pipeline: {}", schedule.pipeline);
+            println!("    cron: {}", schedule.cron);
+            println!("    timezone: {}", schedule.timezone.name());
+            if !schedule.variables.is_empty() {
+                println!("    variables:");
+                for (name, value) in schedule.variables.iter() {
+                    println!("      {}: {}", name, value);
+                }
+            }
+        }
+    }
+    if !local.hooks.is_empty() {
+        println!("- hooks:");
+        for hook in local.hooks.iter() {
+            println!("  - pipeline: {}", hook.pipeline);
+            println!("    secret: ***********");
+            println!("    header: {}", hook.header);
+            if !hook.variables.is_empty() {
+                println!("    variables:");
+                for (name, pointer) in hook.variables.iter() {
+                    println!("      {}: {}", name, pointer);
+                }
+            }
+        }
+    }
+    if let Some(ha) = &local.high_availability {
+        println!("- high-availability:");
+        println!("  - lease-duration: {}", ha.lease_duration);
+        println!("  - requeue-interrupted: {}", ha.requeue_interrupted);
+    }
+    if let Some(retention) = &local.retention {
+        println!("- retention:");
+        if let Some(max_age) = retention.max_age {
+            println!("  - max-age: {}s", max_age);
+        }
+        if let Some(max_runs) = retention.max_runs_per_pipeline {
+            println!("  - max-runs-per-pipeline: {}", max_runs);
+        }
+        if let Some(max_size) = retention.max_total_size {
+            println!("  - max-total-size: {} bytes", max_size);
+        }
+        println!("  - compress-logs: {}", retention.compress_logs);
+        println!("  - interval: {}s", retention.interval);
+    }
+    let logging = &local.logging;
+    let sinks = |sinks: &[LogSink]| {
+        sinks
+            .iter()
+            .map(|s| s.to_string())
+            .collect::<Vec<String>>()
+            .join(", ")
+    };
+    println!("- logging:");
+    println!("  - run-sinks: [{}]", sinks(&logging.run_sinks));
+    println!("  - server-sinks: [{}]", sinks(&logging.server_sinks));
+    if let Some(directory) = &logging.directory {
+        println!("  - directory: {}", directory);
+    }
+    println!("  - ring-size: {}", logging.ring_size);
+    println!("  - syslog-socket: {}", logging.syslog_socket);
     Ok(())
 }
 
@@ -33,6 +117,12 @@ fn list_remote(remote: &BldRemoteConfig) -> Result<()> {
         println!("- name: {}", server.name);
         println!("- host: {}", server.host);
         println!("- port: {}", server.port);
+        if server.hosts.len() > 1 {
+            println!("- hosts:");
+            for (host, port) in server.hosts.iter() {
+                println!("  - {}:{}", host, port);
+            }
+        }
         match &server.auth {
             Auth::OAuth2(info) => {
                 println!("- auth:");
@@ -0,0 +1,29 @@
+use crate::config::definitions;
+use yaml_rust::Yaml;
+
+#[derive(Debug)]
+pub struct BldHaConfig {
+    pub lease_duration: u64,
+    pub requeue_interrupted: bool,
+}
+
+impl BldHaConfig {
+    pub fn load(yaml: &Yaml) -> Option<Self> {
+        if yaml.is_badvalue() {
+            return None;
+        }
+        let lease_duration = yaml["lease-duration"]
+            .as_i64()
+            .map(|d| d.max(1) as u64)
+            .or(Some(definitions::LOCAL_LEASE_DURATION))
+            .unwrap();
+        let requeue_interrupted = yaml["requeue-interrupted"]
+            .as_bool()
+            .or(Some(false))
+            .unwrap();
+        Some(Self {
+            lease_duration,
+            requeue_interrupted,
+        })
+    }
+}
@@ -0,0 +1,85 @@
+use crate::config::definitions::HOOK_SIGNATURE_HEADER;
+use crate::types::Result;
+use hmac::{Hmac, Mac, NewMac};
+use serde_json::Value;
+use sha2::Sha256;
+use std::collections::HashMap;
+use yaml_rust::Yaml;
+
+#[derive(Debug)]
+pub struct BldHookConfig {
+    pub pipeline: String,
+    pub secret: String,
+    pub header: String,
+    pub variables: HashMap<String, String>,
+}
+
+impl BldHookConfig {
+    pub fn load(yaml: &Yaml) -> Result<Self> {
+        let pipeline = yaml["pipeline"]
+            .as_str()
+            .ok_or("Hook entry must define a pipeline")?
+            .to_string();
+        let secret = match (yaml["secret"].as_str(), yaml["secret-file"].as_str()) {
+            (Some(secret), _) => secret.to_string(),
+            (None, Some(path)) => std::fs::read_to_string(path)?.trim().to_string(),
+            (None, None) => return Err("Hook entry must define a secret".into()),
+        };
+        let header = yaml["header"]
+            .as_str()
+            .or(Some(HOOK_SIGNATURE_HEADER))
+            .unwrap()
+            .to_string();
+        let mut variables = HashMap::new();
+        if let Some(entries) = yaml["variables"].as_hash() {
+            for (name, pointer) in entries.iter() {
+                let name = name.as_str().ok_or("Hook variable names must be strings")?;
+                let pointer = pointer
+                    .as_str()
+                    .ok_or("Hook variables must map to a json pointer")?;
+                variables.insert(name.to_string(), pointer.to_string());
+            }
+        }
+        Ok(Self {
+            pipeline,
+            secret,
+            header,
+            variables,
+        })
+    }
+
+    pub fn verify(&self, signature: &str, payload: &[u8]) -> bool {
+        let signature = signature.trim_start_matches("sha256=");
+        let signature = match hex::decode(signature) {
+            Ok(signature) => signature,
+            Err(_) => return false,
+        };
+        let mut mac = match Hmac::<Sha256>::new_varkey(self.secret.as_bytes()) {
+            Ok(mac) => mac,
+            Err(_) => return false,
+        };
+        mac.update(payload);
+        mac.verify(&signature).is_ok()
+    }
+
+    fn quote(value: &str) -> String {
+        let safe = |c: char| c.is_ascii_alphanumeric() || ",._+:@%/-".contains(c);
+        if !value.is_empty() && value.chars().all(safe) {
+            return value.to_string();
+        }
+        format!("'{}'", value.replace('\'', "'\\''"))
+    }
+
+    pub fn variables(&self, payload: &Value) -> HashMap<String, String> {
+        let mut variables = HashMap::new();
+        for (name, pointer) in self.variables.iter() {
+            let value = match payload.pointer(pointer) {
+                Some(Value::String(value)) => value.to_string(),
+                Some(Value::Null) | None => continue,
+                Some(value) => value.to_string(),
+            };
+            variables.insert(name.to_string(), BldHookConfig::quote(&value));
+        }
+        variables
+    }
+}
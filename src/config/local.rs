@@ -1,6 +1,9 @@
 use crate::config::definitions;
-use crate::config::AuthValidation;
-use crate::types::Result;
+use crate::config::{
+    AuthValidation, BldDockerTlsConfig, BldHaConfig, BldHookConfig, BldLoggingConfig,
+    BldRegistryConfig, BldRetentionConfig, BldScheduleConfig,
+};
+use crate::types::{Result, EMPTY_YAML_VEC};
 use yaml_rust::Yaml;
 
 #[derive(Debug)]
@@ -12,6 +15,17 @@ pub struct BldLocalConfig {
     pub db: String,
     pub auth: AuthValidation,
     pub docker_url: String,
+    pub docker_tls: Option<BldDockerTlsConfig>,
+    pub registries: Vec<BldRegistryConfig>,
+    pub stop_grace_period: u64,
+    pub max_concurrent_runs: usize,
+    pub server_runs: bool,
+    pub record_runs: bool,
+    pub schedules: Vec<BldScheduleConfig>,
+    pub hooks: Vec<BldHookConfig>,
+    pub high_availability: Option<BldHaConfig>,
+    pub retention: Option<BldRetentionConfig>,
+    pub logging: BldLoggingConfig,
 }
 
 impl BldLocalConfig {
@@ -23,7 +37,19 @@ impl BldLocalConfig {
             logs: definitions::LOCAL_LOGS.to_string(),
             db: definitions::LOCAL_DB.to_string(),
             auth: AuthValidation::None,
-            docker_url: definitions::LOCAL_DOCKER_URL.to_string(),
+            docker_url: std::env::var("DOCKER_HOST")
+                .unwrap_or_else(|_| definitions::LOCAL_DOCKER_URL.to_string()),
+            docker_tls: BldDockerTlsConfig::from_env(),
+            registries: Vec::new(),
+            stop_grace_period: definitions::LOCAL_STOP_GRACE_PERIOD,
+            max_concurrent_runs: definitions::LOCAL_MAX_CONCURRENT_RUNS,
+            server_runs: definitions::LOCAL_SERVER_RUNS,
+            record_runs: definitions::LOCAL_RECORD_RUNS,
+            schedules: Vec::new(),
+            hooks: Vec::new(),
+            high_availability: None,
+            retention: None,
+            logging: BldLoggingConfig::default(),
         }
     }
 
@@ -52,11 +78,59 @@ impl BldLocalConfig {
             .or(Some(definitions::LOCAL_DB))
             .unwrap()
             .to_string();
-        let docker_url = local_yaml["docker-url"]
-            .as_str()
-            .or(Some(definitions::LOCAL_DOCKER_URL))
+        let (docker_url, docker_tls) = match local_yaml["docker-url"].as_str() {
+            Some(url) => (
+                url.to_string(),
+                BldDockerTlsConfig::load(&local_yaml["docker-tls"])?,
+            ),
+            None => (
+                std::env::var("DOCKER_HOST")
+                    .unwrap_or_else(|_| definitions::LOCAL_DOCKER_URL.to_string()),
+                BldDockerTlsConfig::from_env(),
+            ),
+        };
+        let registries = local_yaml["registries"]
+            .as_vec()
+            .or(Some(&EMPTY_YAML_VEC))
             .unwrap()
-            .to_string();
+            .iter()
+            .map(|r| BldRegistryConfig::load(r))
+            .collect::<Result<Vec<BldRegistryConfig>>>()?;
+        let stop_grace_period = local_yaml["stop-grace-period"]
+            .as_i64()
+            .map(|p| p.max(0) as u64)
+            .or(Some(definitions::LOCAL_STOP_GRACE_PERIOD))
+            .unwrap();
+        let max_concurrent_runs = local_yaml["max-concurrent-runs"]
+            .as_i64()
+            .map(|r| r.max(1) as usize)
+            .or(Some(definitions::LOCAL_MAX_CONCURRENT_RUNS))
+            .unwrap();
+        let server_runs = local_yaml["server-runs"]
+            .as_bool()
+            .or(Some(definitions::LOCAL_SERVER_RUNS))
+            .unwrap();
+        let record_runs = local_yaml["record-runs"]
+            .as_bool()
+            .or(Some(definitions::LOCAL_RECORD_RUNS))
+            .unwrap();
+        let schedules = local_yaml["schedules"]
+            .as_vec()
+            .or(Some(&EMPTY_YAML_VEC))
+            .unwrap()
+            .iter()
+            .map(|s| BldScheduleConfig::load(s))
+            .collect::<Result<Vec<BldScheduleConfig>>>()?;
+        let hooks = local_yaml["hooks"]
+            .as_vec()
+            .or(Some(&EMPTY_YAML_VEC))
+            .unwrap()
+            .iter()
+            .map(|h| BldHookConfig::load(h))
+            .collect::<Result<Vec<BldHookConfig>>>()?;
+        let high_availability = BldHaConfig::load(&local_yaml["high-availability"]);
+        let retention = BldRetentionConfig::load(&local_yaml["retention"])?;
+        let logging = BldLoggingConfig::load(&local_yaml["logging"])?;
         let auth = BldLocalConfig::auth_load(local_yaml)?;
         Ok(Self {
             server_mode,
@@ -66,9 +140,43 @@ impl BldLocalConfig {
             db,
             auth,
             docker_url,
+            docker_tls,
+            registries,
+            stop_grace_period,
+            max_concurrent_runs,
+            server_runs,
+            record_runs,
+            schedules,
+            hooks,
+            high_availability,
+            retention,
+            logging,
+        })
+    }
+
+    pub fn registry(&self, image: &str) -> Option<&BldRegistryConfig> {
+        let mut parts = image.splitn(2, '/');
+        let host = match (parts.next(), parts.next()) {
+            (Some(host), Some(_))
+                if host.contains('.') || host.contains(':') || host == "localhost" =>
+            {
+                host
+            }
+            _ => definitions::DOCKER_HUB_REGISTRY,
+        };
+        self.registries.iter().find(|r| {
+            let url = r
+                .url
+                .trim_start_matches("https://")
+                .trim_start_matches("http://");
+            url.trim_end_matches('/') == host
         })
     }
 
+    pub fn hook(&self, pipeline: &str) -> Option<&BldHookConfig> {
+        self.hooks.iter().find(|h| h.pipeline == pipeline)
+    }
+
     fn auth_load(yaml: &Yaml) -> Result<AuthValidation> {
         let auth_validation = match yaml["auth"]["method"].as_str() {
             Some("ldap") => AuthValidation::Ldap,
@@ -0,0 +1,107 @@
+use crate::config::definitions::{LOCAL_LOGGING_RING_SIZE, LOCAL_LOGGING_SYSLOG_SOCKET};
+use crate::types::{BldError, Result, EMPTY_YAML_VEC};
+use yaml_rust::Yaml;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LogSink {
+    Terminal,
+    File,
+    Json,
+    Syslog,
+    Ring,
+}
+
+impl LogSink {
+    pub fn parse(value: &str) -> Result<Self> {
+        match value {
+            "terminal" => Ok(Self::Terminal),
+            "file" => Ok(Self::File),
+            "json" => Ok(Self::Json),
+            "syslog" => Ok(Self::Syslog),
+            "ring" => Ok(Self::Ring),
+            _ => Err(BldError::Other(format!("unknown log sink {}", value))),
+        }
+    }
+
+    fn load_all(yaml: &Yaml) -> Result<Vec<Self>> {
+        yaml.as_vec()
+            .or(Some(&EMPTY_YAML_VEC))
+            .unwrap()
+            .iter()
+            .map(|s| Self::parse(s.as_str().unwrap_or_default()))
+            .collect()
+    }
+}
+
+impl ToString for LogSink {
+    fn to_string(&self) -> String {
+        match self {
+            Self::Terminal => "terminal",
+            Self::File => "file",
+            Self::Json => "json",
+            Self::Syslog => "syslog",
+            Self::Ring => "ring",
+        }
+        .to_string()
+    }
+}
+
+#[derive(Debug)]
+pub struct BldLoggingConfig {
+    pub run_sinks: Vec<LogSink>,
+    pub server_sinks: Vec<LogSink>,
+    pub directory: Option<String>,
+    pub ring_size: usize,
+    pub syslog_socket: String,
+}
+
+impl BldLoggingConfig {
+    pub fn default() -> Self {
+        Self {
+            run_sinks: vec![LogSink::Terminal],
+            server_sinks: Vec::new(),
+            directory: None,
+            ring_size: LOCAL_LOGGING_RING_SIZE,
+            syslog_socket: LOCAL_LOGGING_SYSLOG_SOCKET.to_string(),
+        }
+    }
+
+    pub fn load(yaml: &Yaml) -> Result<Self> {
+        if yaml.is_badvalue() {
+            return Ok(Self::default());
+        }
+        let run_sinks = match yaml["run-sinks"].is_badvalue() {
+            true => vec![LogSink::Terminal],
+            false => LogSink::load_all(&yaml["run-sinks"])?,
+        };
+        let server_sinks = LogSink::load_all(&yaml["server-sinks"])?;
+        if let Some(sink) = server_sinks
+            .iter()
+            .find(|s| **s == LogSink::Json || **s == LogSink::Ring)
+        {
+            let message = format!(
+                "log sink {} can't be used in server-sinks",
+                sink.to_string()
+            );
+            return Err(BldError::Other(message));
+        }
+        let directory = yaml["directory"].as_str().map(|d| d.to_string());
+        let ring_size = yaml["ring-size"]
+            .as_i64()
+            .map(|s| s.max(1) as usize)
+            .or(Some(LOCAL_LOGGING_RING_SIZE))
+            .unwrap();
+        let syslog_socket = yaml["syslog-socket"]
+            .as_str()
+            .or(Some(LOCAL_LOGGING_SYSLOG_SOCKET))
+            .unwrap()
+            .to_string();
+        Ok(Self {
+            run_sinks,
+            server_sinks,
+            directory,
+            ring_size,
+            syslog_socket,
+        })
+    }
+}
@@ -1,16 +1,30 @@
 mod auth;
 mod cli;
 pub mod definitions;
+mod docker;
 mod exec;
+mod ha;
+mod hook;
 mod local;
+mod logging;
+mod registry;
 mod remote;
+mod retention;
+mod schedule;
 mod server;
 
 pub use auth::*;
 pub use cli::*;
+pub use docker::*;
 pub use exec::*;
+pub use ha::*;
+pub use hook::*;
 pub use local::*;
+pub use logging::*;
+pub use registry::*;
 pub use remote::*;
+pub use retention::*;
+pub use schedule::*;
 pub use server::*;
 
 use crate::path;
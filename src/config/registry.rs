@@ -0,0 +1,41 @@
+use crate::types::Result;
+use shiplift::RegistryAuth;
+use yaml_rust::Yaml;
+
+#[derive(Debug)]
+pub struct BldRegistryConfig {
+    pub url: String,
+    pub username: String,
+    pub password: String,
+}
+
+impl BldRegistryConfig {
+    pub fn load(yaml: &Yaml) -> Result<Self> {
+        let url = yaml["url"]
+            .as_str()
+            .ok_or("Registry entry must define a url")?
+            .to_string();
+        let username = yaml["username"]
+            .as_str()
+            .ok_or("Registry entry must define a username")?
+            .to_string();
+        let password = match (yaml["password"].as_str(), yaml["password-file"].as_str()) {
+            (Some(password), _) => password.to_string(),
+            (None, Some(path)) => std::fs::read_to_string(path)?.trim().to_string(),
+            (None, None) => return Err("Registry entry must define a password".into()),
+        };
+        Ok(Self {
+            url,
+            username,
+            password,
+        })
+    }
+
+    pub fn auth(&self) -> RegistryAuth {
+        RegistryAuth::builder()
+            .username(&self.username)
+            .password(&self.password)
+            .server_address(&self.url)
+            .build()
+    }
+}
@@ -0,0 +1,51 @@
+use crate::config::definitions::LOCAL_RETENTION_INTERVAL;
+use crate::types::{parse_duration, parse_size, Result};
+use yaml_rust::Yaml;
+
+#[derive(Debug)]
+pub struct BldRetentionConfig {
+    pub max_age: Option<u64>,
+    pub max_runs_per_pipeline: Option<usize>,
+    pub max_total_size: Option<u64>,
+    pub compress_logs: bool,
+    pub interval: u64,
+}
+
+impl BldRetentionConfig {
+    fn value(yaml: &Yaml) -> Option<String> {
+        match yaml {
+            Yaml::String(value) => Some(value.to_string()),
+            Yaml::Integer(value) => Some(value.to_string()),
+            _ => None,
+        }
+    }
+
+    pub fn load(yaml: &Yaml) -> Result<Option<Self>> {
+        if yaml.is_badvalue() {
+            return Ok(None);
+        }
+        let max_age = match Self::value(&yaml["max-age"]) {
+            Some(age) => Some(parse_duration(&age)?),
+            None => None,
+        };
+        let max_runs_per_pipeline = yaml["max-runs-per-pipeline"]
+            .as_i64()
+            .map(|r| r.max(1) as usize);
+        let max_total_size = match Self::value(&yaml["max-total-size"]) {
+            Some(size) => Some(parse_size(&size)?),
+            None => None,
+        };
+        let compress_logs = yaml["compress-logs"].as_bool().or(Some(false)).unwrap();
+        let interval = match Self::value(&yaml["interval"]) {
+            Some(interval) => parse_duration(&interval)?.max(1),
+            None => LOCAL_RETENTION_INTERVAL,
+        };
+        Ok(Some(Self {
+            max_age,
+            max_runs_per_pipeline,
+            max_total_size,
+            compress_logs,
+            interval,
+        }))
+    }
+}
@@ -0,0 +1,69 @@
+use crate::types::{yaml_string_map, BldError, Result};
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+use cron::Schedule;
+use std::collections::HashMap;
+use std::str::FromStr;
+use yaml_rust::Yaml;
+
+#[derive(Debug)]
+pub struct BldScheduleConfig {
+    pub pipeline: String,
+    pub cron: String,
+    pub schedule: Schedule,
+    pub timezone: Tz,
+    pub variables: HashMap<String, String>,
+}
+
+impl BldScheduleConfig {
+    fn expression(cron: &str) -> String {
+        match cron.split_whitespace().count() {
+            5 => format!("0 {}", cron),
+            _ => cron.to_string(),
+        }
+    }
+
+    pub fn load(yaml: &Yaml) -> Result<Self> {
+        let pipeline = yaml["pipeline"]
+            .as_str()
+            .ok_or("Schedule entry must define a pipeline")?
+            .to_string();
+        let cron = yaml["cron"]
+            .as_str()
+            .ok_or("Schedule entry must define a cron expression")?
+            .to_string();
+        let schedule = Schedule::from_str(&Self::expression(&cron)).map_err(|e| {
+            BldError::ParseError(format!("invalid cron expression {}: {}", cron, e))
+        })?;
+        let timezone = yaml["timezone"]
+            .as_str()
+            .or(Some("UTC"))
+            .unwrap()
+            .parse::<Tz>()
+            .map_err(BldError::ParseError)?;
+        let variables = yaml_string_map(&yaml["variables"]);
+        Ok(Self {
+            pipeline,
+            cron,
+            schedule,
+            timezone,
+            variables,
+        })
+    }
+
+    pub fn next(&self) -> Option<DateTime<Tz>> {
+        self.schedule.upcoming(self.timezone).next()
+    }
+
+    pub fn after(&self, date_time: &DateTime<Tz>) -> Option<DateTime<Tz>> {
+        self.schedule.after(date_time).next()
+    }
+
+    pub fn delay(date_time: &DateTime<Tz>) -> std::time::Duration {
+        date_time
+            .with_timezone(&Utc)
+            .signed_duration_since(Utc::now())
+            .to_std()
+            .unwrap_or_else(|_| std::time::Duration::from_secs(0))
+    }
+}
@@ -2,28 +2,64 @@ use crate::config::{Auth, OAuth2Info};
 use crate::types::Result;
 use yaml_rust::Yaml;
 
+pub fn authority(host: &str, port: i64) -> String {
+    match host.contains(':') {
+        true => format!("[{}]:{}", host, port),
+        false => format!("{}:{}", host, port),
+    }
+}
+
 #[derive(Debug)]
 pub struct BldServerConfig {
     pub name: String,
     pub host: String,
     pub port: i64,
+    pub hosts: Vec<(String, i64)>,
     pub auth: Auth,
     pub same_auth_as: Option<String>,
 }
 
 impl BldServerConfig {
+    fn parse_host(host: &str, port: Option<i64>) -> Result<(String, i64)> {
+        let port_or_default = |port: Option<i64>| port.ok_or("Server entry must define a port");
+        if let Some(bracketed) = host.strip_prefix('[') {
+            let index = bracketed
+                .find(']')
+                .ok_or("Server entry has an invalid IPv6 address")?;
+            let address = bracketed[..index].to_string();
+            return match bracketed[index + 1..].strip_prefix(':') {
+                Some(explicit) => Ok((address, explicit.parse()?)),
+                None => Ok((address, port_or_default(port)?)),
+            };
+        }
+        match host.matches(':').count() {
+            1 => {
+                let index = host.find(':').unwrap();
+                Ok((host[..index].to_string(), host[index + 1..].parse()?))
+            }
+            _ => Ok((host.to_string(), port_or_default(port)?)),
+        }
+    }
+
     pub fn load(yaml: &Yaml) -> Result<Self> {
         let name = yaml["server"]
             .as_str()
             .ok_or("Server entry must have a name")?
             .to_string();
-        let host = yaml["host"]
-            .as_str()
-            .ok_or("Server entry must define a host address")?
-            .to_string();
-        let port = yaml["port"]
-            .as_i64()
-            .ok_or("Server entry must define a port")?;
+        let port = yaml["port"].as_i64();
+        let hosts = match &yaml["host"] {
+            Yaml::String(host) => vec![BldServerConfig::parse_host(host, port)?],
+            Yaml::Array(hosts) => hosts
+                .iter()
+                .filter_map(|h| h.as_str())
+                .map(|h| BldServerConfig::parse_host(h, port))
+                .collect::<Result<Vec<(String, i64)>>>()?,
+            _ => Vec::new(),
+        };
+        let (host, port) = hosts
+            .first()
+            .cloned()
+            .ok_or("Server entry must define a host address")?;
         let auth = match yaml["auth"]["method"].as_str() {
             Some("ldap") => Auth::Ldap,
             Some("oauth2") => Auth::OAuth2(OAuth2Info::load(&host, port, &yaml["auth"])?),
@@ -34,8 +70,16 @@ impl BldServerConfig {
             name,
             host,
             port,
+            hosts,
             auth,
             same_auth_as,
         })
     }
+
+    pub fn urls(&self, path: &str) -> Vec<String> {
+        self.hosts
+            .iter()
+            .map(|(host, port)| format!("http://{}{}", authority(host, *port), path))
+            .collect()
+    }
 }
@@ -1,11 +1,12 @@
 use crate::config::{definitions::REMOTE_SERVER_OAUTH2, Auth};
 use crate::helpers::term::print_error;
 use crate::path;
-use crate::types::Result;
+use crate::types::{BldError, Result};
 use actix::{Arbiter, System};
+use actix_codec::Framed;
 use actix_http::Payload;
 use actix_web::{client::Client, dev::Decompress, error::PayloadError};
-use awc::{http::StatusCode, ClientResponse};
+use awc::{http::StatusCode, ws::Codec, BoxedSocket, ClientResponse};
 use bytes::Bytes;
 use futures::Stream;
 use serde::Serialize;
@@ -28,6 +29,7 @@ async fn handle_response(resp: &mut ServerResponse) {
     let res = match resp.status() {
         StatusCode::OK => handle_body(&body),
         StatusCode::BAD_REQUEST => handle_body(&body),
+        StatusCode::SERVICE_UNAVAILABLE => handle_body(&body),
         StatusCode::UNAUTHORIZED => String::from("unauthorized"),
         _ => String::from("unexpected response from server"),
     };
@@ -47,19 +49,155 @@ pub fn headers(server: &str, auth: &Auth) -> Result<HashMap<String, String>> {
     Ok(headers)
 }
 
-pub fn exec_get(sys: String, url: String, headers: HashMap<String, String>) {
-    let system = System::new(sys);
-    Arbiter::spawn(async move {
-        let client = Client::default();
+pub async fn ws_connect(
+    urls: Vec<String>,
+    headers: &HashMap<String, String>,
+) -> Result<(String, Framed<BoxedSocket, Codec>)> {
+    let mut error = BldError::Other("no server address available".to_string());
+    for url in urls.into_iter() {
+        let mut client = Client::new().ws(&url[..]);
+        for (key, value) in headers.iter() {
+            client = client.header(&key[..], &value[..]);
+        }
+        match client.connect().await {
+            Ok((_, framed)) => return Ok((url, framed)),
+            Err(e) => error = e.into(),
+        }
+    }
+    Err(error)
+}
+
+async fn unavailable(response: &mut ServerResponse) -> BldError {
+    let body = response.body().await;
+    BldError::Other(handle_body(&body))
+}
+
+async fn fetch_response(response: &mut ServerResponse) -> Result<Bytes> {
+    let body = response.body().limit(usize::MAX).await?;
+    match response.status() {
+        StatusCode::OK => Ok(body),
+        StatusCode::UNAUTHORIZED => Err(BldError::Other("unauthorized".to_string())),
+        _ => Err(BldError::Other(String::from_utf8_lossy(&body).to_string())),
+    }
+}
+
+pub async fn fetch_get(urls: Vec<String>, headers: HashMap<String, String>) -> Result<Bytes> {
+    let client = Client::default();
+    let mut error = BldError::Other("no server address available".to_string());
+    for url in urls.into_iter() {
+        let mut request = client.get(url);
+        for (key, value) in headers.iter() {
+            request = request.header(&key[..], &value[..]);
+        }
+        match request.header("User-Agent", "Bld").send().await {
+            Ok(mut response) if response.status() == StatusCode::SERVICE_UNAVAILABLE => {
+                error = unavailable(&mut response).await;
+            }
+            Ok(mut response) => return fetch_response(&mut response).await,
+            Err(e) => error = e.into(),
+        }
+    }
+    Err(error)
+}
+
+async fn fetch_range_response(response: &mut ServerResponse) -> Result<(Bytes, u64)> {
+    let body = response.body().limit(usize::MAX).await?;
+    match response.status() {
+        StatusCode::OK => Ok((body, 0)),
+        StatusCode::PARTIAL_CONTENT => {
+            let start = response
+                .headers()
+                .get("Content-Range")
+                .and_then(|r| r.to_str().ok())
+                .and_then(|r| r.strip_prefix("bytes "))
+                .and_then(|r| r.split('-').next())
+                .and_then(|s| s.parse::<u64>().ok())
+                .unwrap_or(0);
+            Ok((body, start))
+        }
+        StatusCode::RANGE_NOT_SATISFIABLE => Ok((Bytes::new(), 0)),
+        StatusCode::UNAUTHORIZED => Err(BldError::Other("unauthorized".to_string())),
+        _ => Err(BldError::Other(String::from_utf8_lossy(&body).to_string())),
+    }
+}
+
+pub async fn fetch_suffix(
+    urls: Vec<String>,
+    headers: HashMap<String, String>,
+    suffix: u64,
+) -> Result<(Bytes, u64)> {
+    let client = Client::default();
+    let range = format!("bytes=-{}", suffix);
+    let mut error = BldError::Other("no server address available".to_string());
+    for url in urls.into_iter() {
         let mut request = client.get(url);
         for (key, value) in headers.iter() {
             request = request.header(&key[..], &value[..]);
         }
-        let mut response = request.header("User-Agent", "Bld").send().await;
-        match response.as_mut() {
-            Ok(resp) => handle_response(resp).await,
-            Err(e) => {
-                let _ = print_error(&e.to_string());
+        match request
+            .header("User-Agent", "Bld")
+            .header("Range", &range[..])
+            .send()
+            .await
+        {
+            Ok(mut response) if response.status() == StatusCode::SERVICE_UNAVAILABLE => {
+                error = unavailable(&mut response).await;
+            }
+            Ok(mut response) => return fetch_range_response(&mut response).await,
+            Err(e) => error = e.into(),
+        }
+    }
+    Err(error)
+}
+
+pub async fn fetch_post<T>(
+    urls: Vec<String>,
+    headers: HashMap<String, String>,
+    body: T,
+) -> Result<Bytes>
+where
+    T: Serialize,
+{
+    let client = Client::default();
+    let mut error = BldError::Other("no server address available".to_string());
+    for url in urls.into_iter() {
+        let mut request = client.post(url);
+        for (key, value) in headers.iter() {
+            request = request.header(&key[..], &value[..]);
+        }
+        match request.header("User-Agent", "Bld").send_json(&body).await {
+            Ok(mut response) if response.status() == StatusCode::SERVICE_UNAVAILABLE => {
+                error = unavailable(&mut response).await;
+            }
+            Ok(mut response) => return fetch_response(&mut response).await,
+            Err(e) => error = e.into(),
+        }
+    }
+    Err(error)
+}
+
+pub fn exec_get(sys: String, urls: Vec<String>, headers: HashMap<String, String>) {
+    let system = System::new(sys);
+    Arbiter::spawn(async move {
+        let client = Client::default();
+        let count = urls.len();
+        for (index, url) in urls.into_iter().enumerate() {
+            let mut request = client.get(url);
+            for (key, value) in headers.iter() {
+                request = request.header(&key[..], &value[..]);
+            }
+            let mut response = request.header("User-Agent", "Bld").send().await;
+            match response.as_mut() {
+                Ok(resp)
+                    if resp.status() == StatusCode::SERVICE_UNAVAILABLE && index + 1 < count => {}
+                Ok(resp) => {
+                    handle_response(resp).await;
+                    break;
+                }
+                Err(e) if index + 1 == count => {
+                    let _ = print_error(&e.to_string());
+                }
+                Err(_) => {}
             }
         }
         System::current().stop();
@@ -67,22 +205,30 @@ pub fn exec_get(sys: String, url: String, headers: HashMap<String, String>) {
     let _ = system.run();
 }
 
-pub fn exec_post<T>(sys: String, url: String, headers: HashMap<String, String>, body: T)
+pub fn exec_post<T>(sys: String, urls: Vec<String>, headers: HashMap<String, String>, body: T)
 where
     T: 'static + Serialize,
 {
     let system = System::new(&sys);
     Arbiter::spawn(async move {
         let client = Client::default();
-        let mut request = client.post(url);
-        for (key, value) in headers.iter() {
-            request = request.header(&key[..], &value[..]);
-        }
-        let mut response = request.header("User-Agent", "Bld").send_json(&body).await;
-        match response.as_mut() {
-            Ok(r) => handle_response(r).await,
-            Err(e) => {
-                let _ = print_error(&e.to_string());
+        let count = urls.len();
+        for (index, url) in urls.into_iter().enumerate() {
+            let mut request = client.post(url);
+            for (key, value) in headers.iter() {
+                request = request.header(&key[..], &value[..]);
+            }
+            let mut response = request.header("User-Agent", "Bld").send_json(&body).await;
+            match response.as_mut() {
+                Ok(r) if r.status() == StatusCode::SERVICE_UNAVAILABLE && index + 1 < count => {}
+                Ok(r) => {
+                    handle_response(r).await;
+                    break;
+                }
+                Err(e) if index + 1 == count => {
+                    let _ = print_error(&e.to_string());
+                }
+                Err(_) => {}
             }
         }
         System::current().stop();
@@ -7,8 +7,16 @@ pub fn command() -> App<'static, 'static> {
         .long("server")
         .takes_value(true)
         .help("The name of the server from which to fetch execution history");
+    let local = Arg::with_name("local")
+        .long("local")
+        .help("Fetches the execution history of the runs recorded on the local machine");
+    let pipeline_id = Arg::with_name("pipeline-id")
+        .short("i")
+        .long("pipeline-id")
+        .takes_value(true)
+        .help("The id of a run to show along with the timing summary of its steps");
     SubCommand::with_name("hist")
         .about("Fetches execution history of pipelines on a server")
         .version(VERSION)
-        .args(&[server])
+        .args(&[server, local, pipeline_id])
 }
@@ -1,11 +1,62 @@
 use crate::config::BldConfig;
 use crate::helpers::errors::auth_for_server_invalid;
 use crate::helpers::request::{exec_get, headers};
-use crate::types::Result;
+use crate::persist::Database;
+use crate::types::{BldError, Result};
 use clap::ArgMatches;
 
+pub fn history_info(config: &BldConfig) -> Result<String> {
+    let db = Database::connect(&config.local.db)?;
+    let pipelines = db.all()?;
+    let mut info = String::new();
+    for pipeline in pipelines.iter() {
+        info.push_str(&format!("\n{}\n", pipeline.to_string()));
+        for checkout in db.checkouts(&pipeline.id)?.iter() {
+            info.push_str(&format!("{}\n", checkout.to_string()));
+        }
+        for downstream in db.downstream(&pipeline.id)?.iter() {
+            info.push_str(&format!(
+                "DOWNSTREAM: {} {} {}\n",
+                downstream.name, downstream.id, downstream.state
+            ));
+        }
+    }
+    Ok(info)
+}
+
+pub fn run_info(config: &BldConfig, id: &str) -> Result<String> {
+    let db = Database::connect(&config.local.db)?;
+    let pipeline = match db.pipeline(id) {
+        Some(pipeline) => pipeline,
+        None => return Err(BldError::Other(format!("no run with id {} found", id))),
+    };
+    let mut info = format!("\n{}\n", pipeline.to_string());
+    for checkout in db.checkouts(&pipeline.id)?.iter() {
+        info.push_str(&format!("{}\n", checkout.to_string()));
+    }
+    for downstream in db.downstream(&pipeline.id)?.iter() {
+        info.push_str(&format!(
+            "DOWNSTREAM: {} {} {}\n",
+            downstream.name, downstream.id, downstream.state
+        ));
+    }
+    let summary = db.run_summary(&pipeline.id)?;
+    if !summary.is_empty() {
+        info.push_str(&format!("\n{}\n", summary.table()));
+    }
+    Ok(info)
+}
+
 pub fn exec(matches: &ArgMatches<'_>) -> Result<()> {
     let config = BldConfig::load()?;
+    let id = matches.value_of("pipeline-id");
+    if matches.is_present("local") {
+        match id {
+            Some(id) => println!("{}", run_info(&config, id)?),
+            None => println!("{}", history_info(&config)?),
+        }
+        return Ok(());
+    }
     let srv = config.remote.server_or_first(matches.value_of("server"))?;
     let (name, auth) = match &srv.same_auth_as {
         Some(name) => match config.remote.servers.iter().find(|s| &s.name == name) {
@@ -15,8 +66,11 @@ pub fn exec(matches: &ArgMatches<'_>) -> Result<()> {
         None => (&srv.name, &srv.auth),
     };
     let sys = String::from("bld-hist");
-    let url = format!("http://{}:{}/hist", srv.host, srv.port);
+    let urls = match id {
+        Some(id) => srv.urls(&format!("/hist/{}", id)),
+        None => srv.urls("/hist"),
+    };
     let headers = headers(name, auth)?;
-    exec_get(sys, url, headers);
+    exec_get(sys, urls, headers);
     Ok(())
 }
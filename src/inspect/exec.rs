@@ -19,9 +19,9 @@ pub fn exec(matches: &ArgMatches<'_>) -> Result<()> {
         },
         None => (&srv.name, &srv.auth),
     };
-    let url = format!("http://{}:{}/inspect/{}", srv.host, srv.port, pip);
+    let urls = srv.urls(&format!("/inspect/{}", pip));
     let headers = headers(name, auth)?;
     let sys = String::from("bld-inspect");
-    exec_get(sys, url, headers);
+    exec_get(sys, urls, headers);
     Ok(())
 }
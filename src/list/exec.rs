@@ -15,8 +15,8 @@ pub fn exec(matches: &ArgMatches<'_>) -> Result<()> {
         None => (&srv.name, &srv.auth),
     };
     let sys = String::from("bld-ls");
-    let url = format!("http://{}:{}/list", srv.host, srv.port);
+    let urls = srv.urls("/list");
     let headers = headers(name, auth)?;
-    exec_get(sys, url, headers);
+    exec_get(sys, urls, headers);
     Ok(())
 }
@@ -0,0 +1,39 @@
+use crate::config::definitions::VERSION;
+use clap::{App, Arg, SubCommand};
+
+pub fn command() -> App<'static, 'static> {
+    let pipeline_id = Arg::with_name("pipeline-id")
+        .short("i")
+        .long("pipeline-id")
+        .help("The id of the run of which to fetch the log")
+        .takes_value(true)
+        .required(true);
+    let server = Arg::with_name("server")
+        .short("s")
+        .long("server")
+        .help("The name of the server from which to fetch the log")
+        .takes_value(true);
+    let output = Arg::with_name("output")
+        .short("o")
+        .long("output")
+        .help("The path of a file to write the log to instead of the terminal")
+        .takes_value(true);
+    let tail = Arg::with_name("tail")
+        .long("tail")
+        .help("Fetch only the last lines of the log")
+        .takes_value(true);
+    let raw = Arg::with_name("raw")
+        .long("raw")
+        .help("Write the log as the JSON lines stored by the server");
+    let timestamps = Arg::with_name("timestamps")
+        .short("t")
+        .long("timestamps")
+        .help("Prefix each line of the log with its timestamp");
+    let steps = Arg::with_name("steps")
+        .long("steps")
+        .help("Prefix each line of the log with the name of its step");
+    SubCommand::with_name("logs")
+        .about("Fetches the full log of a run from a bld server")
+        .version(VERSION)
+        .args(&[pipeline_id, server, output, tail, raw, timestamps, steps])
+}
@@ -0,0 +1,129 @@
+use crate::config::definitions::LOGS_TAIL_LINE_SIZE;
+use crate::config::BldConfig;
+use crate::helpers::errors::auth_for_server_invalid;
+use crate::helpers::request::{fetch_get, fetch_suffix, headers};
+use crate::types::{LogEntry, Result};
+use actix::System;
+use bytes::Bytes;
+use clap::ArgMatches;
+use std::collections::HashMap;
+
+struct LogsOutput {
+    output: Option<String>,
+    tail: Option<usize>,
+    raw: bool,
+    timestamps: bool,
+    steps: bool,
+}
+
+impl LogsOutput {
+    fn write(&self, content: &Bytes) -> Result<()> {
+        let content = String::from_utf8_lossy(content);
+        let lines: Vec<&str> = content.lines().collect();
+        let skip = match self.tail {
+            Some(tail) => lines.len().saturating_sub(tail),
+            None => 0,
+        };
+        let mut text = String::new();
+        for line in lines.iter().skip(skip) {
+            match self.raw {
+                true => text.push_str(line),
+                false => text.push_str(&LogEntry::parse(line).render(self.timestamps, self.steps)),
+            }
+            text.push('\n');
+        }
+        match &self.output {
+            Some(path) => std::fs::write(path, text)?,
+            None => print!("{}", text),
+        }
+        Ok(())
+    }
+}
+
+async fn fetch_tail(
+    urls: Vec<String>,
+    headers: HashMap<String, String>,
+    tail: usize,
+) -> Result<Bytes> {
+    let mut suffix = (tail as u64 + 1) * LOGS_TAIL_LINE_SIZE;
+    loop {
+        let (content, start) = fetch_suffix(urls.clone(), headers.clone(), suffix).await?;
+        if let Some(content) = tail_lines(content, start, tail) {
+            return Ok(content);
+        }
+        suffix = suffix.saturating_mul(4);
+    }
+}
+
+fn tail_lines(content: Bytes, start: u64, tail: usize) -> Option<Bytes> {
+    if start == 0 {
+        return Some(content);
+    }
+    let lines = content.iter().filter(|b| **b == b'\n').count();
+    if lines <= tail {
+        return None;
+    }
+    let first = content
+        .iter()
+        .position(|b| *b == b'\n')
+        .map(|p| p + 1)
+        .unwrap_or(0);
+    Some(content.slice(first..))
+}
+
+pub fn exec(matches: &ArgMatches<'_>) -> Result<()> {
+    let config = BldConfig::load()?;
+    let id = matches.value_of("pipeline-id").unwrap_or_default();
+    let tail = match matches.value_of("tail") {
+        Some(tail) => Some(tail.parse::<usize>()?),
+        None => None,
+    };
+    let output = LogsOutput {
+        output: matches.value_of("output").map(|o| o.to_string()),
+        tail,
+        raw: matches.is_present("raw"),
+        timestamps: matches.is_present("timestamps"),
+        steps: matches.is_present("steps"),
+    };
+    let srv = config.remote.server_or_first(matches.value_of("server"))?;
+    let (name, auth) = match &srv.same_auth_as {
+        Some(name) => match config.remote.servers.iter().find(|s| &s.name == name) {
+            Some(srv) => (&srv.name, &srv.auth),
+            None => return auth_for_server_invalid(),
+        },
+        None => (&srv.name, &srv.auth),
+    };
+    let urls = srv.urls(&format!("/logs/{}", id));
+    let headers = headers(name, auth)?;
+    let mut system = System::new("bld-logs");
+    let content = match tail {
+        Some(tail) => system.block_on(fetch_tail(urls, headers, tail))?,
+        None => system.block_on(fetch_get(urls, headers))?,
+    };
+    output.write(&content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tail_lines_keeps_content_from_the_start_of_the_log() {
+        let content = Bytes::from_static(b"first\nsecond\n");
+        let tail = tail_lines(content.clone(), 0, 5);
+        assert_eq!(tail, Some(content));
+    }
+
+    #[test]
+    fn tail_lines_drops_the_partial_first_line() {
+        let content = Bytes::from_static(b"rtial\nsecond\nthird\n");
+        let tail = tail_lines(content, 10, 2);
+        assert_eq!(tail, Some(Bytes::from_static(b"second\nthird\n")));
+    }
+
+    #[test]
+    fn tail_lines_asks_for_more_when_lines_are_missing() {
+        let content = Bytes::from_static(b"rtial\nsecond\n");
+        assert_eq!(tail_lines(content, 10, 2), None);
+    }
+}
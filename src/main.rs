@@ -5,14 +5,21 @@ mod hist;
 mod init;
 mod inspect;
 mod list;
+mod logs;
 mod monit;
 mod os;
 mod persist;
+mod prune;
 mod push;
+mod queue;
+mod rerun;
 mod run;
+mod schedules;
+mod search;
 mod server;
 mod stop;
 mod types;
+mod worker;
 
 use crate::config::definitions::VERSION;
 use crate::helpers::term::print_error;
@@ -29,11 +36,18 @@ fn main() {
             hist::command(),
             config::command(),
             run::command(),
+            schedules::command(),
+            search::command(),
             server::command(),
             monit::command(),
             list::command(),
+            logs::command(),
             push::command(),
+            prune::command(),
+            queue::command(),
+            rerun::command(),
             stop::command(),
+            worker::command(),
         ])
         .get_matches();
 
@@ -44,11 +58,18 @@ fn main() {
         ("hist", Some(matches)) => hist::exec(matches),
         ("config", Some(matches)) => config::exec(matches),
         ("run", Some(matches)) => run::exec(matches),
+        ("schedules", Some(matches)) => schedules::exec(matches),
+        ("search", Some(matches)) => search::exec(matches),
         ("server", Some(matches)) => server::exec(matches),
         ("monit", Some(matches)) => monit::exec(matches),
         ("ls", Some(matches)) => list::exec(matches),
+        ("logs", Some(matches)) => logs::exec(matches),
         ("push", Some(matches)) => push::exec(matches),
+        ("prune", Some(matches)) => prune::exec(matches),
+        ("queue", Some(matches)) => queue::exec(matches),
+        ("rerun", Some(matches)) => rerun::exec(matches),
         ("stop", Some(matches)) => stop::exec(matches),
+        ("worker", Some(matches)) => worker::exec(matches),
         _ => Ok(()),
     };
 
@@ -21,8 +21,29 @@ pub fn command() -> App<'static, 'static> {
         .long("last")
         .help("Monitor the execution of the last invoked pipeline. Takes precedence over pipeline-id and pipeline")
         .takes_value(false);
+    let local = Arg::with_name("local")
+        .long("local")
+        .help("Monitor a run recorded on the local machine")
+        .takes_value(false);
+    let timestamps = Arg::with_name("timestamps")
+        .short("t")
+        .long("timestamps")
+        .help("Prefix each line of the output with its timestamp")
+        .takes_value(false);
+    let steps = Arg::with_name("steps")
+        .long("steps")
+        .help("Prefix each line of the output with the name of its step")
+        .takes_value(false);
     SubCommand::with_name("monit")
         .about("Connects to a bld server to monitor the execution of a pipeline")
         .version(VERSION)
-        .args(&vec![pipeline_id, pipeline, server, last])
+        .args(&vec![
+            pipeline_id,
+            pipeline,
+            server,
+            last,
+            local,
+            timestamps,
+            steps,
+        ])
 }
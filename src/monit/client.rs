@@ -1,27 +1,44 @@
-use crate::types::MonitInfo;
+use crate::config::definitions::{MONIT_RECONNECT_ATTEMPTS, MONIT_RECONNECT_DELAY};
+use crate::helpers::term::print_error;
+use crate::monit::{remote_invoke, MonitConnectionInfo};
+use crate::types::{LogEntry, MonitInfo};
 use actix::io::{SinkWrite, WriteHandler};
-use actix::{Actor, ActorContext, AsyncContext, Context, Handler, StreamHandler, System};
+use actix::{Actor, ActorContext, Arbiter, AsyncContext, Context, Handler, StreamHandler, System};
 use actix_codec::Framed;
 use awc::error::WsProtocolError;
 use awc::ws::{Codec, Frame, Message};
 use awc::BoxedSocket;
 use bytes::Bytes;
 use futures::stream::SplitSink;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 pub struct MonitClient {
     writer: SinkWrite<Message, SplitSink<Framed<BoxedSocket, Codec>, Message>>,
+    info: MonitConnectionInfo,
+    hb: Instant,
+    closed: bool,
 }
 
 impl MonitClient {
-    pub fn new(writer: SinkWrite<Message, SplitSink<Framed<BoxedSocket, Codec>, Message>>) -> Self {
-        Self { writer }
+    pub fn new(
+        writer: SinkWrite<Message, SplitSink<Framed<BoxedSocket, Codec>, Message>>,
+        info: MonitConnectionInfo,
+    ) -> Self {
+        Self {
+            writer,
+            info,
+            hb: Instant::now(),
+            closed: false,
+        }
     }
 
     fn heartbeat(&mut self, ctx: &mut Context<Self>) {
         ctx.run_interval(Duration::new(1, 0), |act, ctx| {
+            if Instant::now().duration_since(act.hb) > Duration::from_secs(10) {
+                ctx.stop();
+                return;
+            }
             let _ = act.writer.write(Message::Ping(Bytes::from_static(b"")));
-            act.heartbeat(ctx);
         });
     }
 }
@@ -34,7 +51,21 @@ impl Actor for MonitClient {
     }
 
     fn stopped(&mut self, _: &mut Context<Self>) {
-        System::current().stop();
+        if self.closed {
+            System::current().stop();
+            return;
+        }
+        let info = self.info.clone();
+        Arbiter::spawn(async move {
+            for _ in 0..MONIT_RECONNECT_ATTEMPTS {
+                tokio::time::delay_for(Duration::from_secs(MONIT_RECONNECT_DELAY)).await;
+                if remote_invoke(info.clone()).await.is_ok() {
+                    return;
+                }
+            }
+            let _ = print_error("connection to the server was lost");
+            System::current().stop();
+        });
     }
 }
 
@@ -49,10 +80,25 @@ impl Handler<MonitInfo> for MonitClient {
 }
 
 impl StreamHandler<Result<Frame, WsProtocolError>> for MonitClient {
-    fn handle(&mut self, msg: Result<Frame, WsProtocolError>, _: &mut Context<Self>) {
+    fn handle(&mut self, msg: Result<Frame, WsProtocolError>, ctx: &mut Context<Self>) {
+        self.hb = Instant::now();
         match msg {
-            Ok(Frame::Text(bt)) => println!("{}", String::from_utf8_lossy(&bt)),
-            Ok(Frame::Close(_)) => System::current().stop(),
+            Ok(Frame::Text(bt)) => {
+                let entry = LogEntry::parse(&String::from_utf8_lossy(&bt));
+                if entry.offset.is_some() {
+                    self.info.monit.from_offset = entry.offset;
+                }
+                entry.print(self.info.timestamps, self.info.steps);
+            }
+            Ok(Frame::Binary(bt)) => {
+                self.info.monit.id = Some(String::from_utf8_lossy(&bt).to_string());
+                self.info.monit.name = None;
+                self.info.monit.last = false;
+            }
+            Ok(Frame::Close(_)) => {
+                self.closed = true;
+                ctx.stop();
+            }
             _ => {}
         }
     }
@@ -1,38 +1,37 @@
 use crate::config::BldConfig;
 use crate::helpers::errors::auth_for_server_invalid;
-use crate::helpers::request::headers;
+use crate::helpers::request::{headers, ws_connect};
 use crate::helpers::term::print_error;
 use crate::monit::MonitClient;
-use crate::types::{MonitInfo, Result};
+use crate::path;
+use crate::persist::{Database, FileScanner, Scanner};
+use crate::types::{BldError, MonitInfo, Result};
 use actix::{io::SinkWrite, Actor, Arbiter, StreamHandler, System};
-use awc::Client;
 use clap::ArgMatches;
 use futures::stream::StreamExt;
 use std::collections::HashMap;
+use std::path::PathBuf;
+use std::thread;
+use std::time::Duration;
 
-struct MonitConnectionInfo {
-    host: String,
-    port: i64,
-    headers: HashMap<String, String>,
-    pip_id: Option<String>,
-    pip_name: Option<String>,
-    pip_last: bool,
+#[derive(Clone)]
+pub struct MonitConnectionInfo {
+    pub urls: Vec<String>,
+    pub headers: HashMap<String, String>,
+    pub monit: MonitInfo,
+    pub timestamps: bool,
+    pub steps: bool,
 }
 
-async fn remote_invoke(info: MonitConnectionInfo) -> Result<()> {
-    let url = format!("http://{}:{}/ws-monit", info.host, info.port);
-    let mut client = Client::new().ws(url);
-    for (key, value) in info.headers.iter() {
-        client = client.header(&key[..], &value[..]);
-    }
-    let (_, framed) = client.connect().await?;
+pub async fn remote_invoke(info: MonitConnectionInfo) -> Result<()> {
+    let (_, framed) = ws_connect(info.urls.clone(), &info.headers).await?;
     let (sink, stream) = framed.split();
+    let monit = info.monit.clone();
     let addr = MonitClient::create(|ctx| {
         MonitClient::add_stream(stream, ctx);
-        MonitClient::new(SinkWrite::new(sink, ctx))
+        MonitClient::new(SinkWrite::new(sink, ctx), info)
     });
-    addr.send(MonitInfo::new(info.pip_id, info.pip_name, info.pip_last))
-        .await?;
+    addr.send(monit).await?;
     Ok(())
 }
 
@@ -47,11 +46,52 @@ fn exec_request(info: MonitConnectionInfo) {
     let _ = system.run();
 }
 
+fn local_monit(config: &BldConfig, info: MonitInfo, timestamps: bool, steps: bool) -> Result<()> {
+    let mut db = Database::connect(&config.local.db)?;
+    if info.last {
+        db.load_last();
+    } else if let Some(id) = &info.id {
+        db.load(id);
+    } else if let Some(name) = &info.name {
+        db.load_by_name(name);
+    }
+    let (id, name) = match &db.pipeline {
+        Some(pipeline) => (pipeline.id.clone(), pipeline.name.clone()),
+        None => return Err(BldError::Other("pipeline not found".to_string())),
+    };
+    let path = path![&config.local.logs, format!("{}-{}", name, id)]
+        .display()
+        .to_string();
+    let mut scanner = FileScanner::new(&path)?;
+    loop {
+        for entry in scanner.fetch().iter() {
+            entry.print(timestamps, steps);
+        }
+        db.load(&id);
+        match &db.pipeline {
+            Some(pipeline) if pipeline.running || pipeline.is_queued() => {
+                thread::sleep(Duration::from_secs(1))
+            }
+            _ => break,
+        }
+    }
+    for entry in scanner.finish().iter() {
+        entry.print(timestamps, steps);
+    }
+    Ok(())
+}
+
 pub fn exec(matches: &ArgMatches<'_>) -> Result<()> {
     let config = BldConfig::load()?;
     let pip_id = matches.value_of("pipeline-id").map(|x| x.to_string());
     let pip_name = matches.value_of("pipeline").map(|x| x.to_string());
     let pip_last = matches.is_present("last");
+    let timestamps = matches.is_present("timestamps");
+    let steps = matches.is_present("steps");
+    let monit = MonitInfo::new(pip_id, pip_name, pip_last);
+    if matches.is_present("local") {
+        return local_monit(&config, monit, timestamps, steps);
+    }
     let srv = config.remote.server_or_first(matches.value_of("server"))?;
     let (name, auth) = match &srv.same_auth_as {
         Some(name) => match config.remote.servers.iter().find(|s| &s.name == name) {
@@ -61,12 +101,11 @@ pub fn exec(matches: &ArgMatches<'_>) -> Result<()> {
         None => (&srv.name, &srv.auth),
     };
     exec_request(MonitConnectionInfo {
-        host: srv.host.to_string(),
-        port: srv.port,
+        urls: srv.urls("/ws-monit"),
         headers: headers(name, auth)?,
-        pip_id,
-        pip_name,
-        pip_last,
+        monit,
+        timestamps,
+        steps,
     });
     Ok(())
 }
@@ -1,7 +1,5 @@
 #![allow(dead_code)]
 
-use std::path::PathBuf;
-
 #[macro_export]
 macro_rules! path {
     ($($x:expr), *) => {
@@ -23,36 +21,14 @@ pub enum OSname {
     Unknown,
 }
 
-fn is_linux() -> bool {
-    let lsb_path = path!["/", "etc", "lsb-release"];
-    if lsb_path.is_file() {
-        return true;
-    };
-    let deb_path = path!["/", "etc", "debian-version"];
-    if deb_path.is_file() {
-        return true;
-    }
-    false
-}
-
-fn is_mac() -> bool {
-    let path = path!["System", "Library", "CoreServices", "SystemVersion.plist"];
-    path.is_file()
-}
-
-fn is_windows() -> bool {
-    let path = path!["C:\\", "Windows", "System32"];
-    path.is_dir()
-}
-
 pub fn name() -> OSname {
-    if is_linux() {
+    if cfg!(target_os = "linux") {
         return OSname::Linux;
     }
-    if is_mac() {
+    if cfg!(target_os = "macos") {
         return OSname::Mac;
     }
-    if is_windows() {
+    if cfg!(target_os = "windows") {
         return OSname::Windows;
     }
     OSname::Unknown
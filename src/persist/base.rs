@@ -1,16 +1,23 @@
-use crate::types::Result;
+use crate::types::{LogEntry, Result, RunState, RunSummary};
+use std::collections::HashMap;
 
 pub trait Logger {
-    fn dump(&mut self, text: &str);
+    fn step(&mut self, name: Option<&str>);
     fn dumpln(&mut self, text: &str);
+    fn stdout(&mut self, line: &str);
+    fn stderr(&mut self, line: &str);
     fn info(&mut self, text: &str);
     fn error(&mut self, text: &str);
 }
 
 pub trait Scanner {
-    fn fetch(&mut self) -> Vec<String>;
+    fn fetch(&mut self) -> Vec<LogEntry>;
 }
 
 pub trait Execution {
-    fn update(&mut self, is_running: bool) -> Result<()>;
+    fn id(&self) -> Option<String>;
+    fn update(&mut self, state: RunState) -> Result<()>;
+    fn checkout(&mut self, repository: &str, reference: &str, commit: &str) -> Result<()>;
+    fn trigger(&mut self, pipeline: &str, variables: HashMap<String, String>) -> Result<()>;
+    fn summary(&mut self, summary: &RunSummary) -> Result<()>;
 }
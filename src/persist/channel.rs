@@ -0,0 +1,71 @@
+use crate::persist::Logger;
+use std::sync::{Arc, Mutex};
+use tokio::runtime::Handle;
+use tokio::sync::mpsc::{self, UnboundedSender};
+
+enum LogMessage {
+    Step(Option<String>),
+    Dump(String),
+    Stdout(String),
+    Stderr(String),
+    Info(String),
+    Error(String),
+}
+
+pub struct ChannelLogger {
+    tx: UnboundedSender<LogMessage>,
+}
+
+impl ChannelLogger {
+    pub fn atom<T>(runtime: &Handle, mut inner: T) -> Arc<Mutex<Self>>
+    where
+        T: 'static + Logger + Send,
+    {
+        let (tx, mut rx) = mpsc::unbounded_channel::<LogMessage>();
+        runtime.spawn(async move {
+            while let Some(message) = rx.recv().await {
+                match message {
+                    LogMessage::Step(name) => inner.step(name.as_deref()),
+                    LogMessage::Dump(text) => inner.dumpln(&text),
+                    LogMessage::Stdout(line) => inner.stdout(&line),
+                    LogMessage::Stderr(line) => inner.stderr(&line),
+                    LogMessage::Info(text) => inner.info(&text),
+                    LogMessage::Error(text) => inner.error(&text),
+                }
+            }
+        });
+        Arc::new(Mutex::new(Self { tx }))
+    }
+
+    fn send(&self, message: LogMessage) {
+        if self.tx.send(message).is_err() {
+            eprintln!("Couldn't send message to logger");
+        }
+    }
+}
+
+impl Logger for ChannelLogger {
+    fn step(&mut self, name: Option<&str>) {
+        self.send(LogMessage::Step(name.map(|n| n.to_string())));
+    }
+
+    fn dumpln(&mut self, text: &str) {
+        self.send(LogMessage::Dump(text.to_string()));
+    }
+
+    fn stdout(&mut self, line: &str) {
+        self.send(LogMessage::Stdout(line.to_string()));
+    }
+
+    fn stderr(&mut self, line: &str) {
+        self.send(LogMessage::Stderr(line.to_string()));
+    }
+
+    fn info(&mut self, text: &str) {
+        self.send(LogMessage::Info(text.to_string()));
+    }
+
+    fn error(&mut self, text: &str) {
+        self.send(LogMessage::Error(text.to_string()));
+    }
+}
@@ -0,0 +1,114 @@
+use crate::config::{BldLoggingConfig, LogSink};
+use crate::path;
+use crate::persist::{
+    FileLogger, Logger, RingBuffer, RingLogger, ShellLogger, SyslogLogger, TextLogger,
+};
+use crate::types::Result;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+#[derive(Default)]
+pub struct CompositeLogger {
+    sinks: Vec<Box<dyn Logger + Send>>,
+    ring: Option<RingBuffer>,
+}
+
+impl CompositeLogger {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add<T>(mut self, sink: T) -> Self
+    where
+        T: 'static + Logger + Send,
+    {
+        self.sinks.push(Box::new(sink));
+        self
+    }
+
+    pub fn sinks(
+        mut self,
+        config: &BldLoggingConfig,
+        logs: &str,
+        sinks: &[LogSink],
+        name: &str,
+        id: &str,
+    ) -> Result<Self> {
+        let directory = config.directory.as_deref().unwrap_or(logs);
+        if sinks
+            .iter()
+            .any(|s| *s == LogSink::File || *s == LogSink::Json)
+        {
+            std::fs::create_dir_all(directory)?;
+        }
+        for sink in sinks.iter() {
+            self = match sink {
+                LogSink::Terminal => self.add(ShellLogger),
+                LogSink::File => {
+                    let path = path![directory, format!("{}-{}.log", name, id)];
+                    self.add(TextLogger::new(&path.display().to_string())?)
+                }
+                LogSink::Json => {
+                    let path = path![directory, format!("{}-{}.json", name, id)];
+                    self.add(FileLogger::new(&path.display().to_string())?)
+                }
+                LogSink::Syslog => {
+                    let tag = format!("{}-{}", name, id);
+                    self.add(SyslogLogger::connect(&config.syslog_socket, &tag)?)
+                }
+                LogSink::Ring => {
+                    let ring = RingLogger::new(config.ring_size);
+                    self.ring = Some(ring.buffer());
+                    self.add(ring)
+                }
+            };
+        }
+        Ok(self)
+    }
+
+    pub fn ring(&self) -> Option<RingBuffer> {
+        self.ring.clone()
+    }
+
+    pub fn atom(self) -> Arc<Mutex<Self>> {
+        Arc::new(Mutex::new(self))
+    }
+}
+
+impl Logger for CompositeLogger {
+    fn step(&mut self, name: Option<&str>) {
+        for sink in self.sinks.iter_mut() {
+            sink.step(name);
+        }
+    }
+
+    fn dumpln(&mut self, text: &str) {
+        for sink in self.sinks.iter_mut() {
+            sink.dumpln(text);
+        }
+    }
+
+    fn stdout(&mut self, line: &str) {
+        for sink in self.sinks.iter_mut() {
+            sink.stdout(line);
+        }
+    }
+
+    fn stderr(&mut self, line: &str) {
+        for sink in self.sinks.iter_mut() {
+            sink.stderr(line);
+        }
+    }
+
+    fn info(&mut self, text: &str) {
+        for sink in self.sinks.iter_mut() {
+            sink.info(text);
+        }
+    }
+
+    fn error(&mut self, text: &str) {
+        for sink in self.sinks.iter_mut() {
+            sink.error(text);
+        }
+    }
+}
@@ -0,0 +1,61 @@
+use crate::persist::db::queries::*;
+use crate::types::Result;
+use diesel::query_dsl::RunQueryDsl;
+use diesel::sql_types::Text;
+use diesel::sqlite::SqliteConnection;
+use diesel::{sql_query, Queryable, QueryableByName};
+
+#[derive(Debug, Queryable, QueryableByName)]
+pub struct CheckoutModel {
+    #[sql_type = "Text"]
+    pub pipeline_id: String,
+    #[sql_type = "Text"]
+    pub repository: String,
+    #[sql_type = "Text"]
+    pub reference: String,
+    #[sql_type = "Text"]
+    pub commit_sha: String,
+    #[sql_type = "Text"]
+    pub date_time: String,
+}
+
+impl CheckoutModel {
+    pub fn create(connection: &SqliteConnection) -> Result<()> {
+        sql_query(CREATE_TABLE_CHECKOUT_QUERY).execute(connection)?;
+        Ok(())
+    }
+
+    pub fn select_by_pipeline_id(connection: &SqliteConnection, id: &str) -> Result<Vec<Self>> {
+        let res = sql_query(SELECT_CHECKOUTS_BY_PIPELINE_ID_QUERY)
+            .bind::<Text, _>(id)
+            .load::<Self>(connection)?;
+        Ok(res)
+    }
+
+    pub fn delete_by_pipeline_id(connection: &SqliteConnection, id: &str) -> Result<()> {
+        sql_query(DELETE_CHECKOUTS_BY_PIPELINE_ID_QUERY)
+            .bind::<Text, _>(id)
+            .execute(connection)?;
+        Ok(())
+    }
+
+    pub fn insert(connection: &SqliteConnection, checkout: &Self) -> Result<()> {
+        sql_query(INSERT_CHECKOUT_QUERY)
+            .bind::<Text, _>(&checkout.pipeline_id)
+            .bind::<Text, _>(&checkout.repository)
+            .bind::<Text, _>(&checkout.reference)
+            .bind::<Text, _>(&checkout.commit_sha)
+            .bind::<Text, _>(&checkout.date_time)
+            .execute(connection)?;
+        Ok(())
+    }
+}
+
+impl ToString for CheckoutModel {
+    fn to_string(&self) -> String {
+        format!(
+            "CHECKOUT: {} {} {}",
+            self.repository, self.reference, self.commit_sha
+        )
+    }
+}
@@ -1,11 +1,15 @@
 #![allow(dead_code)]
 use crate::config::definitions::DB_NAME;
 use crate::path;
+use crate::persist::db::migrations;
+use crate::persist::db::queries::CONNECTION_PRAGMAS_QUERY;
 use crate::persist::Execution;
-use crate::persist::PipelineModel;
-use crate::types::{BldError, Result};
+use crate::persist::{CheckoutModel, LeaseModel, PipelineModel, SnapshotModel, StepSummaryModel};
+use crate::types::{BldError, Result, RunState, RunSummary, RunTrigger, StepSummary};
+use diesel::connection::SimpleConnection;
 use diesel::sqlite::SqliteConnection;
 use diesel::Connection;
+use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
 
@@ -16,26 +20,30 @@ fn no_pipeline_instance() -> Result<()> {
 
 pub struct Database {
     pub pipeline: Option<PipelineModel>,
+    pub triggers: Vec<(String, HashMap<String, String>)>,
     connection: SqliteConnection,
 }
 
 impl Database {
     fn initialize(conn: &SqliteConnection) -> Result<()> {
         PipelineModel::create(conn)?;
-        Ok(())
+        CheckoutModel::create(conn)?;
+        SnapshotModel::create(conn)?;
+        LeaseModel::create(conn)?;
+        StepSummaryModel::create(conn)?;
+        migrations::run(conn)
     }
 
     pub fn connect(db: &str) -> Result<Self> {
         let path_buf = path![db, DB_NAME];
         let path_str = path_buf.as_path().display().to_string();
-        let is_new = !path_buf.is_file();
         let connection = SqliteConnection::establish(&path_str)?;
-        if is_new {
-            Database::initialize(&connection)?;
-        }
+        connection.batch_execute(CONNECTION_PRAGMAS_QUERY)?;
+        Database::initialize(&connection)?;
         Ok(Self {
             connection,
             pipeline: None,
+            triggers: Vec::new(),
         })
     }
 
@@ -55,7 +63,100 @@ impl Database {
         self.pipeline = PipelineModel::select_last(&self.connection);
     }
 
-    pub fn add(&mut self, id: &str, name: &str, user: &str) -> Result<()> {
+    pub fn checkouts(&self, id: &str) -> Result<Vec<CheckoutModel>> {
+        CheckoutModel::select_by_pipeline_id(&self.connection, id)
+    }
+
+    pub fn snapshot(
+        &mut self,
+        files: &HashMap<String, String>,
+        variables: &HashMap<String, String>,
+    ) -> Result<()> {
+        let pipeline = match self.pipeline.as_mut() {
+            Some(pipeline) => pipeline,
+            None => return no_pipeline_instance(),
+        };
+        for (name, content) in files.iter() {
+            let snapshot = SnapshotModel {
+                pipeline_id: pipeline.id.clone(),
+                name: name.to_string(),
+                content: content.to_string(),
+            };
+            SnapshotModel::insert(&self.connection, &snapshot)?;
+        }
+        let variables = serde_json::to_string(variables)?;
+        PipelineModel::update_variables(&self.connection, &pipeline.id, &variables)?;
+        pipeline.variables = Some(variables);
+        Ok(())
+    }
+
+    pub fn files(&self, id: &str) -> Result<HashMap<String, String>> {
+        let files = SnapshotModel::select_by_pipeline_id(&self.connection, id)?
+            .into_iter()
+            .map(|s| (s.name, s.content))
+            .collect();
+        Ok(files)
+    }
+
+    pub fn active(&self, name: &str) -> Result<Vec<PipelineModel>> {
+        PipelineModel::select_active_by_name(&self.connection, name)
+    }
+
+    pub fn downstream(&self, id: &str) -> Result<Vec<PipelineModel>> {
+        PipelineModel::select_by_upstream_id(&self.connection, id)
+    }
+
+    pub fn orphaned(&self, instance: &str) -> Result<Vec<PipelineModel>> {
+        PipelineModel::select_orphaned(&self.connection, instance)
+    }
+
+    pub fn interrupt(&self, id: &str) -> Result<()> {
+        let end_date_time = chrono::Utc::now().to_string();
+        let state = RunState::Interrupted.to_string();
+        PipelineModel::update(&self.connection, id, false, &end_date_time, &state)
+    }
+
+    pub fn remove(&self, id: &str) -> Result<()> {
+        CheckoutModel::delete_by_pipeline_id(&self.connection, id)?;
+        SnapshotModel::delete_by_pipeline_id(&self.connection, id)?;
+        StepSummaryModel::delete_by_pipeline_id(&self.connection, id)?;
+        PipelineModel::delete_by_id(&self.connection, id)
+    }
+
+    pub fn pipeline(&self, id: &str) -> Option<PipelineModel> {
+        PipelineModel::select_by_id(&self.connection, id)
+    }
+
+    pub fn run_summary(&self, id: &str) -> Result<RunSummary> {
+        let steps = StepSummaryModel::select_by_pipeline_id(&self.connection, id)?
+            .into_iter()
+            .map(|s| StepSummary {
+                name: s.name,
+                state: s.state,
+                duration: s.duration as u64,
+                image_pull: s.image_pull as u64,
+                artifacts: s.artifacts as u64,
+            })
+            .collect();
+        Ok(RunSummary { steps })
+    }
+
+    pub fn abandoned(&self, instance: &str) -> Result<Vec<PipelineModel>> {
+        PipelineModel::select_by_instance(&self.connection, instance)
+    }
+
+    pub fn acquire_lease(&self, name: &str, holder: &str, duration: u64) -> Result<Option<String>> {
+        LeaseModel::acquire(&self.connection, name, holder, duration as i64)
+    }
+
+    pub fn add(
+        &mut self,
+        id: &str,
+        name: &str,
+        user: &str,
+        trigger: &RunTrigger,
+        instance: Option<&str>,
+    ) -> Result<()> {
         let pipeline = PipelineModel {
             id: id.to_string(),
             name: name.to_string(),
@@ -63,6 +164,12 @@ impl Database {
             user: user.to_string(),
             start_date_time: chrono::Utc::now().to_string(),
             end_date_time: String::new(),
+            state: RunState::Queued.to_string(),
+            trigger: trigger.to_string(),
+            upstream_id: trigger.upstream().map(|u| u.to_string()),
+            variables: None,
+            instance: instance.map(|i| i.to_string()),
+            rerun_of: trigger.rerun_of().map(|r| r.to_string()),
         };
         PipelineModel::insert(&self.connection, &pipeline)?;
         self.pipeline = Some(pipeline);
@@ -71,14 +178,26 @@ impl Database {
 }
 
 impl Execution for Database {
-    fn update(&mut self, running: bool) -> Result<()> {
+    fn id(&self) -> Option<String> {
+        self.pipeline.as_ref().map(|p| p.id.clone())
+    }
+
+    fn update(&mut self, state: RunState) -> Result<()> {
         match self.pipeline.as_mut() {
             Some(mut pip) => {
+                let running = state.is_running();
                 let end_date_time = match running {
                     true => String::new(),
                     false => chrono::Utc::now().to_string(),
                 };
-                match PipelineModel::update(&self.connection, &pip.id, running, &end_date_time) {
+                let state = state.to_string();
+                match PipelineModel::update(
+                    &self.connection,
+                    &pip.id,
+                    running,
+                    &end_date_time,
+                    &state,
+                ) {
                     Ok(_) => {}
                     Err(e) => {
                         eprintln!("{}", e.to_string());
@@ -89,11 +208,54 @@ impl Execution for Database {
                 }
                 pip.running = running;
                 pip.end_date_time = end_date_time;
+                pip.state = state;
                 Ok(())
             }
             None => no_pipeline_instance(),
         }
     }
+
+    fn checkout(&mut self, repository: &str, reference: &str, commit: &str) -> Result<()> {
+        match &self.pipeline {
+            Some(pip) => {
+                let checkout = CheckoutModel {
+                    pipeline_id: pip.id.clone(),
+                    repository: repository.to_string(),
+                    reference: reference.to_string(),
+                    commit_sha: commit.to_string(),
+                    date_time: chrono::Utc::now().to_string(),
+                };
+                CheckoutModel::insert(&self.connection, &checkout)
+            }
+            None => no_pipeline_instance(),
+        }
+    }
+
+    fn trigger(&mut self, pipeline: &str, variables: HashMap<String, String>) -> Result<()> {
+        self.triggers.push((pipeline.to_string(), variables));
+        Ok(())
+    }
+
+    fn summary(&mut self, summary: &RunSummary) -> Result<()> {
+        match &self.pipeline {
+            Some(pip) => self.connection.transaction(|| {
+                for (position, step) in summary.steps.iter().enumerate() {
+                    let model = StepSummaryModel {
+                        pipeline_id: pip.id.clone(),
+                        position: position as i64,
+                        name: step.name.clone(),
+                        state: step.state.clone(),
+                        duration: step.duration as i64,
+                        image_pull: step.image_pull as i64,
+                        artifacts: step.artifacts as i64,
+                    };
+                    StepSummaryModel::insert(&self.connection, &model)?;
+                }
+                Ok(())
+            }),
+            None => no_pipeline_instance(),
+        }
+    }
 }
 
 pub struct NullExec;
@@ -105,7 +267,61 @@ impl NullExec {
 }
 
 impl Execution for NullExec {
-    fn update(&mut self, _running: bool) -> Result<()> {
+    fn id(&self) -> Option<String> {
+        None
+    }
+
+    fn update(&mut self, _state: RunState) -> Result<()> {
+        Ok(())
+    }
+
+    fn checkout(&mut self, _repository: &str, _reference: &str, _commit: &str) -> Result<()> {
+        Ok(())
+    }
+
+    fn trigger(&mut self, _pipeline: &str, _variables: HashMap<String, String>) -> Result<()> {
+        Ok(())
+    }
+
+    fn summary(&mut self, _summary: &RunSummary) -> Result<()> {
         Ok(())
     }
 }
+
+pub struct TrackedExec<T> {
+    inner: T,
+    state: Option<RunState>,
+}
+
+impl<T: Execution> TrackedExec<T> {
+    pub fn atom(inner: T) -> Arc<Mutex<Self>> {
+        Arc::new(Mutex::new(Self { inner, state: None }))
+    }
+
+    pub fn state(&self) -> Option<RunState> {
+        self.state
+    }
+}
+
+impl<T: Execution> Execution for TrackedExec<T> {
+    fn id(&self) -> Option<String> {
+        self.inner.id()
+    }
+
+    fn update(&mut self, state: RunState) -> Result<()> {
+        self.state = Some(state);
+        self.inner.update(state)
+    }
+
+    fn checkout(&mut self, repository: &str, reference: &str, commit: &str) -> Result<()> {
+        self.inner.checkout(repository, reference, commit)
+    }
+
+    fn trigger(&mut self, pipeline: &str, variables: HashMap<String, String>) -> Result<()> {
+        self.inner.trigger(pipeline, variables)
+    }
+
+    fn summary(&mut self, summary: &RunSummary) -> Result<()> {
+        self.inner.summary(summary)
+    }
+}
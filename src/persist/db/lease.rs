@@ -0,0 +1,53 @@
+#![allow(dead_code)]
+use crate::persist::db::queries::*;
+use crate::types::Result;
+use diesel::query_dsl::RunQueryDsl;
+use diesel::sql_types::{BigInt, Text};
+use diesel::sqlite::SqliteConnection;
+use diesel::{sql_query, Connection, Queryable, QueryableByName};
+
+#[derive(Debug, Queryable, QueryableByName)]
+pub struct LeaseModel {
+    #[sql_type = "Text"]
+    pub name: String,
+    #[sql_type = "Text"]
+    pub holder: String,
+    #[sql_type = "BigInt"]
+    pub expires_at: i64,
+}
+
+impl LeaseModel {
+    pub fn create(connection: &SqliteConnection) -> Result<()> {
+        sql_query(CREATE_TABLE_LEASE_QUERY).execute(connection)?;
+        Ok(())
+    }
+
+    pub fn acquire(
+        connection: &SqliteConnection,
+        name: &str,
+        holder: &str,
+        duration: i64,
+    ) -> Result<Option<String>> {
+        connection.transaction(|| {
+            sql_query(INSERT_LEASE_QUERY)
+                .bind::<Text, _>(name)
+                .execute(connection)?;
+            let previous = sql_query(SELECT_LEASE_QUERY)
+                .bind::<Text, _>(name)
+                .get_result::<Self>(connection)?;
+            let now = chrono::Utc::now().timestamp();
+            let rows = sql_query(UPDATE_LEASE_QUERY)
+                .bind::<Text, _>(holder)
+                .bind::<BigInt, _>(now + duration)
+                .bind::<Text, _>(name)
+                .bind::<Text, _>(holder)
+                .bind::<BigInt, _>(now)
+                .execute(connection)?;
+            Ok(if rows == 1 {
+                Some(previous.holder)
+            } else {
+                None
+            })
+        })
+    }
+}
@@ -0,0 +1,33 @@
+use crate::persist::db::queries::*;
+use crate::types::Result;
+use diesel::query_dsl::RunQueryDsl;
+use diesel::sql_types::Integer;
+use diesel::sqlite::SqliteConnection;
+use diesel::{sql_query, Connection, QueryableByName};
+
+#[derive(QueryableByName)]
+struct UserVersion {
+    #[sql_type = "Integer"]
+    user_version: i32,
+}
+
+fn version(connection: &SqliteConnection) -> Result<usize> {
+    let version = sql_query(SELECT_USER_VERSION_QUERY)
+        .load::<UserVersion>(connection)?
+        .pop()
+        .map(|v| v.user_version as usize)
+        .unwrap_or(0);
+    Ok(version)
+}
+
+pub fn run(connection: &SqliteConnection) -> Result<()> {
+    let version = version(connection)?;
+    for (i, migration) in MIGRATIONS.iter().enumerate().skip(version) {
+        connection.transaction::<_, diesel::result::Error, _>(|| {
+            sql_query(*migration).execute(connection)?;
+            sql_query(format!("pragma user_version = {}", i + 1)).execute(connection)?;
+            Ok(())
+        })?;
+    }
+    Ok(())
+}
@@ -1,8 +1,17 @@
+mod checkout;
 mod connect;
+mod lease;
+mod migrations;
 mod pipeline;
 mod queries;
 mod schema;
+mod snapshot;
+mod step_summary;
 
+pub use checkout::*;
 pub use connect::*;
+pub use lease::*;
 pub use pipeline::*;
 pub use schema::*;
+pub use snapshot::*;
+pub use step_summary::*;
@@ -1,9 +1,10 @@
 use crate::persist::db::queries::*;
-use crate::types::Result;
+use crate::types::{Result, RunState};
 use diesel::query_dsl::RunQueryDsl;
-use diesel::sql_types::{Bool, Text};
+use diesel::sql_types::{Bool, Nullable, Text};
 use diesel::sqlite::SqliteConnection;
 use diesel::{sql_query, Queryable, QueryableByName};
+use std::collections::HashMap;
 
 #[derive(Debug, Queryable, QueryableByName)]
 pub struct PipelineModel {
@@ -19,6 +20,18 @@ pub struct PipelineModel {
     pub start_date_time: String,
     #[sql_type = "Text"]
     pub end_date_time: String,
+    #[sql_type = "Text"]
+    pub state: String,
+    #[sql_type = "Text"]
+    pub trigger: String,
+    #[sql_type = "Nullable<Text>"]
+    pub upstream_id: Option<String>,
+    #[sql_type = "Nullable<Text>"]
+    pub variables: Option<String>,
+    #[sql_type = "Nullable<Text>"]
+    pub instance: Option<String>,
+    #[sql_type = "Nullable<Text>"]
+    pub rerun_of: Option<String>,
 }
 
 impl PipelineModel {
@@ -52,6 +65,34 @@ impl PipelineModel {
         query.unwrap().pop()
     }
 
+    pub fn select_active_by_name(connection: &SqliteConnection, name: &str) -> Result<Vec<Self>> {
+        let res = sql_query(SELECT_ACTIVE_PIPELINES_BY_NAME_QUERY)
+            .bind::<Text, _>(name)
+            .load::<Self>(connection)?;
+        Ok(res)
+    }
+
+    pub fn select_by_upstream_id(connection: &SqliteConnection, id: &str) -> Result<Vec<Self>> {
+        let res = sql_query(SELECT_PIPELINES_BY_UPSTREAM_ID_QUERY)
+            .bind::<Text, _>(id)
+            .load::<Self>(connection)?;
+        Ok(res)
+    }
+
+    pub fn select_orphaned(connection: &SqliteConnection, instance: &str) -> Result<Vec<Self>> {
+        let res = sql_query(SELECT_ORPHANED_PIPELINES_QUERY)
+            .bind::<Text, _>(instance)
+            .load::<Self>(connection)?;
+        Ok(res)
+    }
+
+    pub fn select_by_instance(connection: &SqliteConnection, instance: &str) -> Result<Vec<Self>> {
+        let res = sql_query(SELECT_PIPELINES_BY_INSTANCE_QUERY)
+            .bind::<Text, _>(instance)
+            .load::<Self>(connection)?;
+        Ok(res)
+    }
+
     pub fn select_last(connection: &SqliteConnection) -> Option<Self> {
         let query = sql_query(SELECT_LAST_INVOKED_PIPELINE).load::<Self>(connection);
         if query.is_err() {
@@ -60,6 +101,18 @@ impl PipelineModel {
         query.unwrap().pop()
     }
 
+    pub fn update_variables(
+        connection: &SqliteConnection,
+        id: &str,
+        variables: &str,
+    ) -> Result<()> {
+        sql_query(UPDATE_PIPELINE_VARIABLES_QUERY)
+            .bind::<Text, _>(variables)
+            .bind::<Text, _>(id)
+            .execute(connection)?;
+        Ok(())
+    }
+
     pub fn insert(connection: &SqliteConnection, pipeline: &Self) -> Result<()> {
         sql_query(INSERT_PIPELINE_QUERY)
             .bind::<Text, _>(&pipeline.id)
@@ -68,6 +121,18 @@ impl PipelineModel {
             .bind::<Text, _>(&pipeline.user)
             .bind::<Text, _>(&pipeline.start_date_time)
             .bind::<Text, _>(&pipeline.end_date_time)
+            .bind::<Text, _>(&pipeline.state)
+            .bind::<Text, _>(&pipeline.trigger)
+            .bind::<Nullable<Text>, _>(&pipeline.upstream_id)
+            .bind::<Nullable<Text>, _>(&pipeline.instance)
+            .bind::<Nullable<Text>, _>(&pipeline.rerun_of)
+            .execute(connection)?;
+        Ok(())
+    }
+
+    pub fn delete_by_id(connection: &SqliteConnection, id: &str) -> Result<()> {
+        sql_query(DELETE_PIPELINE_QUERY)
+            .bind::<Text, _>(id)
             .execute(connection)?;
         Ok(())
     }
@@ -77,23 +142,46 @@ impl PipelineModel {
         id: &str,
         running: bool,
         end_date_time: &str,
+        state: &str,
     ) -> Result<()> {
         sql_query(UPDATE_PIPELINE_QUERY)
             .bind::<Bool, _>(running)
             .bind::<Text, _>(end_date_time)
+            .bind::<Text, _>(state)
             .bind::<Text, _>(id)
             .execute(connection)?;
         Ok(())
     }
 }
 
+impl PipelineModel {
+    pub fn is_queued(&self) -> bool {
+        self.state == RunState::Queued.to_string()
+    }
+
+    pub fn variable_map(&self) -> Result<HashMap<String, String>> {
+        match &self.variables {
+            Some(variables) => Ok(serde_json::from_str(variables)?),
+            None => Ok(HashMap::new()),
+        }
+    }
+}
+
 impl ToString for PipelineModel {
     fn to_string(&self) -> String {
         let mut info = String::new();
         info.push_str(&format!("ID: {}\n", self.id));
         info.push_str(&format!("NAME: {}\n", self.name));
         info.push_str(&format!("USER: {}\n", self.user));
+        info.push_str(&format!("TRIGGER: {}\n", self.trigger));
+        if let Some(upstream_id) = &self.upstream_id {
+            info.push_str(&format!("UPSTREAM: {}\n", upstream_id));
+        }
+        if let Some(rerun_of) = &self.rerun_of {
+            info.push_str(&format!("RERUN OF: {}\n", rerun_of));
+        }
         info.push_str(&format!("IS RUNNING: {}\n", self.running));
+        info.push_str(&format!("STATE: {}\n", self.state));
         info.push_str(&format!("START TIME: {}\n", self.start_date_time));
         info.push_str(&format!("END TIME: {}", self.end_date_time));
         info
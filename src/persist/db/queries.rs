@@ -1,7 +1,12 @@
 #![allow(dead_code)]
 
+pub const CONNECTION_PRAGMAS_QUERY: &str = r"
+    pragma busy_timeout = 5000;
+    pragma journal_mode = wal;
+";
+
 pub const CREATE_TABLE_PIPELINE_QUERY: &str = r"
-    create table pipeline (
+    create table if not exists pipeline (
         id nvarchar(50) primary key not null,
         name nvarchar(250) not null,
         running boolean,
@@ -35,13 +40,205 @@ pub const SELECT_PIPELINE_BY_NAME_QUERY: &str = r"
     where name = ?
 ";
 
+pub const SELECT_ACTIVE_PIPELINES_BY_NAME_QUERY: &str = r"
+    select *
+    from pipeline
+    where name = ?
+    and (running = 1 or state = 'queued')
+";
+
+pub const SELECT_PIPELINES_BY_UPSTREAM_ID_QUERY: &str = r"
+    select *
+    from pipeline
+    where upstream_id = ?
+    order by start_date_time
+";
+
 pub const INSERT_PIPELINE_QUERY: &str = r"
-    insert into pipeline
-    values (?, ?, ?, ?, ?, ?)
+    insert into pipeline (id, name, running, user, start_date_time, end_date_time, state, trigger, upstream_id, instance, rerun_of)
+    values (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+";
+
+pub const SELECT_ORPHANED_PIPELINES_QUERY: &str = r"
+    select *
+    from pipeline
+    where (running = 1 or state = 'queued')
+    and instance is not null
+    and instance != ?
+    order by start_date_time
+";
+
+pub const SELECT_PIPELINES_BY_INSTANCE_QUERY: &str = r"
+    select *
+    from pipeline
+    where (running = 1 or state = 'queued')
+    and instance = ?
+    order by start_date_time
+";
+
+pub const DELETE_PIPELINE_QUERY: &str = r"
+    delete from pipeline
+    where id = ?
 ";
 
 pub const UPDATE_PIPELINE_QUERY: &str = r"
     update pipeline 
-    set running = ?, end_date_time = ?
+    set running = ?, end_date_time = ?, state = ?
+    where id = ?
+";
+
+pub const CREATE_TABLE_CHECKOUT_QUERY: &str = r"
+    create table if not exists checkout (
+        pipeline_id nvarchar(50) not null,
+        repository nvarchar(500) not null,
+        reference nvarchar(250),
+        commit_sha nvarchar(50) not null,
+        date_time nvarchar(100)
+    )
+";
+
+pub const SELECT_CHECKOUTS_BY_PIPELINE_ID_QUERY: &str = r"
+    select *
+    from checkout
+    where pipeline_id = ?
+    order by date_time
+";
+
+pub const INSERT_CHECKOUT_QUERY: &str = r"
+    insert into checkout
+    values (?, ?, ?, ?, ?)
+";
+
+pub const DELETE_CHECKOUTS_BY_PIPELINE_ID_QUERY: &str = r"
+    delete from checkout
+    where pipeline_id = ?
+";
+
+pub const CREATE_TABLE_SNAPSHOT_QUERY: &str = r"
+    create table if not exists snapshot (
+        pipeline_id nvarchar(50) not null,
+        name nvarchar(250) not null,
+        content text not null
+    )
+";
+
+pub const SELECT_SNAPSHOTS_BY_PIPELINE_ID_QUERY: &str = r"
+    select *
+    from snapshot
+    where pipeline_id = ?
+";
+
+pub const INSERT_SNAPSHOT_QUERY: &str = r"
+    insert into snapshot
+    values (?, ?, ?)
+";
+
+pub const DELETE_SNAPSHOTS_BY_PIPELINE_ID_QUERY: &str = r"
+    delete from snapshot
+    where pipeline_id = ?
+";
+
+pub const UPDATE_PIPELINE_VARIABLES_QUERY: &str = r"
+    update pipeline
+    set variables = ?
     where id = ?
 ";
+
+pub const CREATE_TABLE_STEP_SUMMARY_QUERY: &str = r"
+    create table if not exists step_summary (
+        pipeline_id nvarchar(50) not null,
+        position integer not null,
+        name nvarchar(250) not null,
+        state nvarchar(20) not null,
+        duration integer not null,
+        image_pull integer not null,
+        artifacts integer not null
+    )
+";
+
+pub const SELECT_STEP_SUMMARIES_BY_PIPELINE_ID_QUERY: &str = r"
+    select *
+    from step_summary
+    where pipeline_id = ?
+    order by position
+";
+
+pub const INSERT_STEP_SUMMARY_QUERY: &str = r"
+    insert into step_summary
+    values (?, ?, ?, ?, ?, ?, ?)
+";
+
+pub const DELETE_STEP_SUMMARIES_BY_PIPELINE_ID_QUERY: &str = r"
+    delete from step_summary
+    where pipeline_id = ?
+";
+
+pub const CREATE_TABLE_LEASE_QUERY: &str = r"
+    create table if not exists lease (
+        name nvarchar(50) primary key not null,
+        holder nvarchar(50) not null,
+        expires_at integer not null
+    )
+";
+
+pub const INSERT_LEASE_QUERY: &str = r"
+    insert or ignore into lease
+    values (?, '', 0)
+";
+
+pub const SELECT_LEASE_QUERY: &str = r"
+    select *
+    from lease
+    where name = ?
+";
+
+pub const UPDATE_LEASE_QUERY: &str = r"
+    update lease
+    set holder = ?, expires_at = ?
+    where name = ?
+    and (holder = ? or expires_at < ?)
+";
+
+pub const SELECT_USER_VERSION_QUERY: &str = r"
+    pragma user_version
+";
+
+pub const MIGRATIONS: &[&str] = &[
+    r"
+    alter table pipeline
+    add column state nvarchar(50) not null default 'finished'
+    ",
+    r"
+    update pipeline
+    set state = 'running'
+    where running = 1
+    ",
+    r"
+    alter table pipeline
+    add column trigger nvarchar(50) not null default 'manual'
+    ",
+    r"
+    alter table pipeline
+    add column upstream_id nvarchar(50)
+    ",
+    r"
+    alter table pipeline
+    add column variables text
+    ",
+    r"
+    alter table pipeline
+    add column instance nvarchar(50)
+    ",
+    r"
+    drop table if exists log_line
+    ",
+    r"
+    alter table pipeline
+    add column rerun_of nvarchar(50)
+    ",
+    r"
+    update pipeline
+    set rerun_of = upstream_id, upstream_id = null
+    where trigger = 'rerun'
+    ",
+];
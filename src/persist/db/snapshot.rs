@@ -0,0 +1,46 @@
+use crate::persist::db::queries::*;
+use crate::types::Result;
+use diesel::query_dsl::RunQueryDsl;
+use diesel::sql_types::Text;
+use diesel::sqlite::SqliteConnection;
+use diesel::{sql_query, Queryable, QueryableByName};
+
+#[derive(Debug, Queryable, QueryableByName)]
+pub struct SnapshotModel {
+    #[sql_type = "Text"]
+    pub pipeline_id: String,
+    #[sql_type = "Text"]
+    pub name: String,
+    #[sql_type = "Text"]
+    pub content: String,
+}
+
+impl SnapshotModel {
+    pub fn create(connection: &SqliteConnection) -> Result<()> {
+        sql_query(CREATE_TABLE_SNAPSHOT_QUERY).execute(connection)?;
+        Ok(())
+    }
+
+    pub fn select_by_pipeline_id(connection: &SqliteConnection, id: &str) -> Result<Vec<Self>> {
+        let res = sql_query(SELECT_SNAPSHOTS_BY_PIPELINE_ID_QUERY)
+            .bind::<Text, _>(id)
+            .load::<Self>(connection)?;
+        Ok(res)
+    }
+
+    pub fn delete_by_pipeline_id(connection: &SqliteConnection, id: &str) -> Result<()> {
+        sql_query(DELETE_SNAPSHOTS_BY_PIPELINE_ID_QUERY)
+            .bind::<Text, _>(id)
+            .execute(connection)?;
+        Ok(())
+    }
+
+    pub fn insert(connection: &SqliteConnection, snapshot: &Self) -> Result<()> {
+        sql_query(INSERT_SNAPSHOT_QUERY)
+            .bind::<Text, _>(&snapshot.pipeline_id)
+            .bind::<Text, _>(&snapshot.name)
+            .bind::<Text, _>(&snapshot.content)
+            .execute(connection)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,58 @@
+use crate::persist::db::queries::*;
+use crate::types::Result;
+use diesel::query_dsl::RunQueryDsl;
+use diesel::sql_types::{BigInt, Text};
+use diesel::sqlite::SqliteConnection;
+use diesel::{sql_query, Queryable, QueryableByName};
+
+#[derive(Debug, Queryable, QueryableByName)]
+pub struct StepSummaryModel {
+    #[sql_type = "Text"]
+    pub pipeline_id: String,
+    #[sql_type = "BigInt"]
+    pub position: i64,
+    #[sql_type = "Text"]
+    pub name: String,
+    #[sql_type = "Text"]
+    pub state: String,
+    #[sql_type = "BigInt"]
+    pub duration: i64,
+    #[sql_type = "BigInt"]
+    pub image_pull: i64,
+    #[sql_type = "BigInt"]
+    pub artifacts: i64,
+}
+
+impl StepSummaryModel {
+    pub fn create(connection: &SqliteConnection) -> Result<()> {
+        sql_query(CREATE_TABLE_STEP_SUMMARY_QUERY).execute(connection)?;
+        Ok(())
+    }
+
+    pub fn select_by_pipeline_id(connection: &SqliteConnection, id: &str) -> Result<Vec<Self>> {
+        let res = sql_query(SELECT_STEP_SUMMARIES_BY_PIPELINE_ID_QUERY)
+            .bind::<Text, _>(id)
+            .load::<Self>(connection)?;
+        Ok(res)
+    }
+
+    pub fn delete_by_pipeline_id(connection: &SqliteConnection, id: &str) -> Result<()> {
+        sql_query(DELETE_STEP_SUMMARIES_BY_PIPELINE_ID_QUERY)
+            .bind::<Text, _>(id)
+            .execute(connection)?;
+        Ok(())
+    }
+
+    pub fn insert(connection: &SqliteConnection, summary: &Self) -> Result<()> {
+        sql_query(INSERT_STEP_SUMMARY_QUERY)
+            .bind::<Text, _>(&summary.pipeline_id)
+            .bind::<BigInt, _>(summary.position)
+            .bind::<Text, _>(&summary.name)
+            .bind::<Text, _>(&summary.state)
+            .bind::<BigInt, _>(summary.duration)
+            .bind::<BigInt, _>(summary.image_pull)
+            .bind::<BigInt, _>(summary.artifacts)
+            .execute(connection)?;
+        Ok(())
+    }
+}
@@ -1,9 +1,11 @@
-use crate::persist::{Logger, Scanner};
-use crate::types::{BldError, Result};
-use std::fs::File;
-use std::io::{BufRead, BufReader, Write};
+use crate::persist::{LogIndex, Logger, Scanner};
+use crate::types::{BldError, LogEntry, LogLevel, LogStream, Result};
+use flate2::read::GzDecoder;
+use std::fs::{File, OpenOptions};
+use std::io::{self, Read, Seek, SeekFrom, Write};
 use std::path::Path;
 use std::sync::{Arc, Mutex};
+use tokio::sync::broadcast::Sender;
 
 fn file_not_found() -> Result<FileScanner> {
     let message = String::from("file not found");
@@ -11,47 +13,151 @@ fn file_not_found() -> Result<FileScanner> {
 }
 
 pub struct FileLogger {
+    file_path: String,
     file_handle: File,
+    step: Option<String>,
+    offset: u64,
+    index: Option<LogIndex>,
+    tx: Option<Sender<LogEntry>>,
 }
 
 impl FileLogger {
     pub fn new(file_path: &str) -> Result<Self> {
-        let path = Path::new(file_path);
-        let file_handle = match path.is_file() {
-            true => File::open(&path)?,
-            false => File::create(&path)?,
-        };
-        Ok(Self { file_handle })
+        let file_handle = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(file_path)?;
+        let offset = file_handle.metadata()?.len();
+        Ok(Self {
+            file_path: file_path.to_string(),
+            file_handle,
+            step: None,
+            offset,
+            index: None,
+            tx: None,
+        })
     }
 
-    fn write(&mut self, text: &str) {
-        if let Err(e) = write!(self.file_handle, "{}", text) {
-            eprintln!("Couldn't write to file: {}", e);
+    pub fn index(mut self) -> Self {
+        let path = LogIndex::path(&self.file_path);
+        if self.offset > 0 && !Path::new(&path).is_file() {
+            return self;
         }
+        match LogIndex::open(&self.file_path) {
+            Ok(index) => self.index = Some(index),
+            Err(e) => eprintln!("Couldn't open log index: {}", e.to_string()),
+        }
+        self
+    }
+
+    pub fn publish(mut self, tx: Sender<LogEntry>) -> Self {
+        self.tx = Some(tx);
+        self
     }
 
-    fn writeln(&mut self, text: &str) {
-        if let Err(e) = writeln!(self.file_handle, "{}", text) {
+    fn writeln(&mut self, stream: LogStream, level: LogLevel, text: &str) {
+        let mut entry = LogEntry::new(self.step.as_deref(), stream, level, text);
+        let line = match serde_json::to_string(&entry) {
+            Ok(line) => line,
+            Err(e) => {
+                eprintln!("Couldn't serialize log entry: {}", e);
+                return;
+            }
+        };
+        if let Some(index) = &mut self.index {
+            if let Err(e) = index.add(text) {
+                eprintln!("Couldn't write to log index: {}", e.to_string());
+                if let Some(index) = self.index.take() {
+                    index.remove();
+                }
+            }
+        }
+        if let Err(e) = writeln!(self.file_handle, "{}", line) {
             eprintln!("Couldn't write to file: {}", e);
+            return;
+        }
+        self.offset += line.len() as u64 + 1;
+        if let Some(tx) = &self.tx {
+            entry.offset = Some(self.offset);
+            let _ = tx.send(entry);
         }
     }
 }
 
 impl Logger for FileLogger {
-    fn dump(&mut self, text: &str) {
-        self.write(text);
+    fn step(&mut self, name: Option<&str>) {
+        self.step = name.map(|n| n.to_string());
+    }
+
+    fn dumpln(&mut self, text: &str) {
+        self.writeln(LogStream::System, LogLevel::Info, text);
+    }
+
+    fn stdout(&mut self, line: &str) {
+        self.writeln(LogStream::Stdout, LogLevel::Info, line);
+    }
+
+    fn stderr(&mut self, line: &str) {
+        self.writeln(LogStream::Stderr, LogLevel::Info, line);
+    }
+
+    fn info(&mut self, text: &str) {
+        self.writeln(LogStream::System, LogLevel::Notice, text);
+    }
+
+    fn error(&mut self, text: &str) {
+        self.writeln(LogStream::System, LogLevel::Error, text);
+    }
+}
+
+pub struct TextLogger {
+    file_handle: File,
+    step: Option<String>,
+}
+
+impl TextLogger {
+    pub fn new(file_path: &str) -> Result<Self> {
+        let file_handle = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(file_path)?;
+        Ok(Self {
+            file_handle,
+            step: None,
+        })
+    }
+
+    fn writeln(&mut self, stream: LogStream, level: LogLevel, text: &str) {
+        let entry = LogEntry::new(self.step.as_deref(), stream, level, text);
+        if let Err(e) = writeln!(self.file_handle, "{}", entry.render(true, true)) {
+            eprintln!("Couldn't write to file: {}", e);
+        }
+    }
+}
+
+impl Logger for TextLogger {
+    fn step(&mut self, name: Option<&str>) {
+        self.step = name.map(|n| n.to_string());
     }
 
     fn dumpln(&mut self, text: &str) {
-        self.writeln(text);
+        self.writeln(LogStream::System, LogLevel::Info, text);
+    }
+
+    fn stdout(&mut self, line: &str) {
+        self.writeln(LogStream::Stdout, LogLevel::Info, line);
+    }
+
+    fn stderr(&mut self, line: &str) {
+        self.writeln(LogStream::Stderr, LogLevel::Info, line);
     }
 
     fn info(&mut self, text: &str) {
-        self.writeln(text);
+        self.writeln(LogStream::System, LogLevel::Notice, text);
     }
 
     fn error(&mut self, text: &str) {
-        self.writeln(text);
+        self.writeln(LogStream::System, LogLevel::Error, text);
     }
 }
 
@@ -65,43 +171,140 @@ impl NullLogger {
 }
 
 impl Logger for NullLogger {
-    fn dump(&mut self, _: &str) {}
+    fn step(&mut self, _: Option<&str>) {}
 
     fn dumpln(&mut self, _: &str) {}
 
+    fn stdout(&mut self, _: &str) {}
+
+    fn stderr(&mut self, _: &str) {}
+
     fn info(&mut self, _: &str) {}
 
     fn error(&mut self, _: &str) {}
 }
 
 pub struct FileScanner {
-    file_handle: File,
-    _index: usize,
+    reader: Box<dyn Read + Send>,
+    offset: u64,
+    buffer: Vec<u8>,
+    compressed: bool,
 }
 
 impl FileScanner {
     pub fn new(path: &str) -> Result<Self> {
+        FileScanner::from_offset(path, 0)
+    }
+
+    pub fn from_offset(path: &str, offset: u64) -> Result<Self> {
         let fpath = Path::new(path);
-        let file_handle = match fpath.is_file() {
-            true => File::open(path)?,
-            false => return file_not_found(),
+        let gz_path = format!("{}.gz", path);
+        let (reader, offset, compressed): (Box<dyn Read + Send>, u64, bool) = if fpath.is_file() {
+            let mut file_handle = File::open(path)?;
+            let offset = offset.min(file_handle.metadata()?.len());
+            file_handle.seek(SeekFrom::Start(offset))?;
+            (Box::new(file_handle), offset, false)
+        } else if Path::new(&gz_path).is_file() {
+            let mut decoder = GzDecoder::new(File::open(&gz_path)?);
+            let offset = io::copy(&mut (&mut decoder).take(offset), &mut io::sink())?;
+            (Box::new(decoder), offset, true)
+        } else {
+            return file_not_found();
         };
         Ok(Self {
-            file_handle,
-            _index: 0,
+            reader,
+            offset,
+            buffer: Vec::new(),
+            compressed,
         })
     }
-}
 
-impl Scanner for FileScanner {
-    fn fetch(&mut self) -> Vec<String> {
-        let mut content = Vec::<String>::new();
-        let reader = BufReader::new(&self.file_handle);
-        for (_i, line) in reader.lines().enumerate() {
-            if let Ok(line) = line {
-                content.push(line);
-            }
+    pub fn into_reader(self) -> Box<dyn Read + Send> {
+        self.reader
+    }
+
+    pub fn finish(&mut self) -> Vec<LogEntry> {
+        self.entries(true)
+    }
+
+    fn entries(&mut self, flush: bool) -> Vec<LogEntry> {
+        let mut content = Vec::<LogEntry>::new();
+        if let Err(e) = self.reader.read_to_end(&mut self.buffer) {
+            eprintln!("Couldn't read from file: {}", e);
+            return content;
+        }
+        let mut start = 0;
+        while let Some(end) = self.buffer[start..].iter().position(|b| *b == b'\n') {
+            let line = String::from_utf8_lossy(&self.buffer[start..start + end]);
+            let mut entry = LogEntry::parse(line.trim_end_matches('\r'));
+            self.offset += end as u64 + 1;
+            entry.offset = Some(self.offset);
+            content.push(entry);
+            start += end + 1;
+        }
+        self.buffer.drain(..start);
+        if flush && !self.buffer.is_empty() {
+            let line = String::from_utf8_lossy(&self.buffer);
+            let mut entry = LogEntry::parse(line.trim_end_matches('\r'));
+            self.offset += self.buffer.len() as u64;
+            entry.offset = Some(self.offset);
+            content.push(entry);
+            self.buffer.clear();
         }
         content
     }
 }
+
+impl Scanner for FileScanner {
+    fn fetch(&mut self) -> Vec<LogEntry> {
+        self.entries(self.compressed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use uuid::Uuid;
+
+    fn temp_log() -> String {
+        std::env::temp_dir()
+            .join(format!("bld-fs-{}", Uuid::new_v4()))
+            .display()
+            .to_string()
+    }
+
+    #[test]
+    fn file_scanner_resumes_from_an_offset() {
+        let path = temp_log();
+        let mut logger = FileLogger::new(&path).ok().unwrap();
+        logger.stdout("first");
+        let offset = logger.offset;
+        logger.stdout("second");
+
+        let entries = FileScanner::from_offset(&path, offset)
+            .ok()
+            .unwrap()
+            .fetch();
+        let _ = fs::remove_file(&path);
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].text, "second");
+        assert_eq!(entries[0].offset, Some(logger.offset));
+    }
+
+    #[test]
+    fn file_scanner_emits_the_last_unterminated_line_when_finished() {
+        let path = temp_log();
+        fs::write(&path, "first\nsecond").unwrap();
+
+        let mut scanner = FileScanner::new(&path).ok().unwrap();
+        let fetched = scanner.fetch();
+        let finished = scanner.finish();
+        let _ = fs::remove_file(&path);
+        assert_eq!(fetched.len(), 1);
+        assert_eq!(fetched[0].offset, Some(6));
+        assert_eq!(finished.len(), 1);
+        assert_eq!(finished[0].text, "second");
+        assert_eq!(finished[0].offset, Some(12));
+    }
+}
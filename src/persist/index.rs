@@ -0,0 +1,147 @@
+use crate::types::Result;
+use regex_syntax::hir::{Hir, HirKind, Literal, RepetitionKind, RepetitionRange};
+use regex_syntax::Parser;
+use std::collections::HashSet;
+use std::fs::{self, File, OpenOptions};
+use std::io::{Read, Write};
+use std::path::Path;
+
+pub type Trigram = [u8; 3];
+
+pub struct LogIndex {
+    path: String,
+    file_handle: File,
+    trigrams: HashSet<Trigram>,
+}
+
+impl LogIndex {
+    pub fn path(logs: &str) -> String {
+        format!("{}.idx", logs)
+    }
+
+    pub fn open(logs: &str) -> Result<Self> {
+        let path = LogIndex::path(logs);
+        let trigrams = LogIndex::load(logs)?.unwrap_or_else(HashSet::new);
+        let file_handle = OpenOptions::new().create(true).append(true).open(&path)?;
+        Ok(Self {
+            path,
+            file_handle,
+            trigrams,
+        })
+    }
+
+    pub fn load(logs: &str) -> Result<Option<HashSet<Trigram>>> {
+        let path = LogIndex::path(logs);
+        if !Path::new(&path).is_file() {
+            return Ok(None);
+        }
+        let mut content = Vec::new();
+        File::open(&path)?.read_to_end(&mut content)?;
+        let trigrams = content
+            .chunks_exact(3)
+            .map(|c| [c[0], c[1], c[2]])
+            .collect();
+        Ok(Some(trigrams))
+    }
+
+    pub fn add(&mut self, text: &str) -> Result<()> {
+        let mut added = Vec::new();
+        for trigram in text.as_bytes().windows(3) {
+            let trigram = [trigram[0], trigram[1], trigram[2]];
+            if self.trigrams.insert(trigram) {
+                added.extend_from_slice(&trigram);
+            }
+        }
+        if !added.is_empty() {
+            self.file_handle.write_all(&added)?;
+        }
+        Ok(())
+    }
+
+    pub fn remove(self) {
+        let _ = fs::remove_file(&self.path);
+    }
+
+    pub fn required(pattern: &str) -> Vec<Trigram> {
+        let hir = match Parser::new().parse(pattern) {
+            Ok(hir) => hir,
+            Err(_) => return Vec::new(),
+        };
+        let mut literals = Vec::new();
+        let mut current = Vec::new();
+        LogIndex::literals(&hir, &mut current, &mut literals);
+        LogIndex::flush(&mut current, &mut literals);
+        let mut trigrams = Vec::new();
+        for literal in literals.iter() {
+            for trigram in literal.windows(3) {
+                let trigram = [trigram[0], trigram[1], trigram[2]];
+                if !trigrams.contains(&trigram) {
+                    trigrams.push(trigram);
+                }
+            }
+        }
+        trigrams
+    }
+
+    fn flush(current: &mut Vec<u8>, literals: &mut Vec<Vec<u8>>) {
+        if current.len() >= 3 {
+            literals.push(current.clone());
+        }
+        current.clear();
+    }
+
+    fn literals(hir: &Hir, current: &mut Vec<u8>, literals: &mut Vec<Vec<u8>>) {
+        match hir.kind() {
+            HirKind::Literal(Literal::Unicode(c)) => {
+                let mut buffer = [0; 4];
+                current.extend_from_slice(c.encode_utf8(&mut buffer).as_bytes());
+            }
+            HirKind::Literal(Literal::Byte(b)) => current.push(*b),
+            HirKind::Empty | HirKind::Anchor(_) | HirKind::WordBoundary(_) => {}
+            HirKind::Group(group) => LogIndex::literals(&group.hir, current, literals),
+            HirKind::Concat(children) => {
+                for child in children.iter() {
+                    LogIndex::literals(child, current, literals);
+                }
+            }
+            HirKind::Repetition(repetition) => {
+                LogIndex::flush(current, literals);
+                let required = match &repetition.kind {
+                    RepetitionKind::ZeroOrOne | RepetitionKind::ZeroOrMore => false,
+                    RepetitionKind::OneOrMore => true,
+                    RepetitionKind::Range(RepetitionRange::Exactly(n))
+                    | RepetitionKind::Range(RepetitionRange::AtLeast(n))
+                    | RepetitionKind::Range(RepetitionRange::Bounded(n, _)) => *n > 0,
+                };
+                if required {
+                    let mut inner = Vec::new();
+                    LogIndex::literals(&repetition.hir, &mut inner, literals);
+                    LogIndex::flush(&mut inner, literals);
+                }
+            }
+            HirKind::Class(_) | HirKind::Alternation(_) => LogIndex::flush(current, literals),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn required_collects_trigrams_of_literal_runs() {
+        let trigrams = LogIndex::required("error: (foo|bar)+ end");
+        assert!(trigrams.contains(b"err"));
+        assert!(trigrams.contains(b"r: "));
+        assert!(trigrams.contains(b" en"));
+        assert!(!trigrams.contains(b"foo"));
+        assert!(!trigrams.contains(b": e"));
+    }
+
+    #[test]
+    fn required_is_empty_without_literals() {
+        assert!(LogIndex::required("[a-z]+\\d*").is_empty());
+        assert!(LogIndex::required("(?i)error").is_empty());
+        assert!(LogIndex::required("(").is_empty());
+    }
+}
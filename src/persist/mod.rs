@@ -1,9 +1,21 @@
 mod base;
+mod channel;
+mod composite;
 mod db;
 mod fs;
+mod index;
+mod retention;
+mod ring;
 mod sh;
+mod syslog;
 
 pub use base::*;
+pub use channel::*;
+pub use composite::*;
 pub use db::*;
 pub use fs::*;
+pub use index::*;
+pub use retention::*;
+pub use ring::*;
 pub use sh::*;
+pub use syslog::*;
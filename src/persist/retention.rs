@@ -0,0 +1,254 @@
+use crate::config::definitions::LOCAL_MACHINE_TMP_DIR;
+use crate::config::{BldConfig, BldRetentionConfig};
+use crate::path;
+use crate::persist::{Database, LogIndex, PipelineModel};
+use crate::types::{BldError, Result};
+use chrono::{NaiveDateTime, Utc};
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, File};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+pub enum PruneAction {
+    RemoveRun {
+        id: String,
+        name: String,
+        logs: String,
+        reason: String,
+    },
+    CompressLog {
+        id: String,
+        logs: String,
+    },
+    RemoveWorkspace {
+        path: String,
+    },
+}
+
+impl ToString for PruneAction {
+    fn to_string(&self) -> String {
+        match self {
+            Self::RemoveRun {
+                id, name, reason, ..
+            } => format!("remove run {} of pipeline {} ({})", id, name, reason),
+            Self::CompressLog { id, .. } => format!("compress log of run {}", id),
+            Self::RemoveWorkspace { path } => format!("remove workspace {}", path),
+        }
+    }
+}
+
+pub struct Retention<'a> {
+    config: &'a BldConfig,
+    retention: &'a BldRetentionConfig,
+}
+
+impl<'a> Retention<'a> {
+    pub fn new(config: &'a BldConfig) -> Result<Self> {
+        match &config.local.retention {
+            Some(retention) => Ok(Self { config, retention }),
+            None => Err(BldError::Other(
+                "no retention settings found in the config".to_string(),
+            )),
+        }
+    }
+
+    fn logs(&self, run: &PipelineModel) -> String {
+        path![&self.config.local.logs, format!("{}-{}", run.name, run.id)]
+            .display()
+            .to_string()
+    }
+
+    fn sinks(&self, name: &str, id: &str) -> Vec<String> {
+        let logging = &self.config.local.logging;
+        let directory = logging
+            .directory
+            .as_deref()
+            .unwrap_or(&self.config.local.logs);
+        ["log", "json"]
+            .iter()
+            .map(|ext| {
+                path![directory, format!("{}-{}.{}", name, id, ext)]
+                    .display()
+                    .to_string()
+            })
+            .collect()
+    }
+
+    fn size(&self, run: &PipelineModel) -> u64 {
+        let logs = self.logs(run);
+        let gz = format!("{}.gz", logs);
+        let size = fs::metadata(&logs)
+            .or_else(|_| fs::metadata(&gz))
+            .map(|m| m.len())
+            .unwrap_or(0);
+        let mut paths = self.sinks(&run.name, &run.id);
+        paths.push(LogIndex::path(&logs));
+        paths
+            .iter()
+            .filter_map(|s| fs::metadata(s).ok())
+            .map(|m| m.len())
+            .fold(size, |total, len| total + len)
+    }
+
+    fn age(run: &PipelineModel) -> Option<u64> {
+        let date_time = match run.end_date_time.is_empty() {
+            true => &run.start_date_time,
+            false => &run.end_date_time,
+        };
+        let date_time =
+            NaiveDateTime::parse_from_str(date_time, "%Y-%m-%d %H:%M:%S%.f UTC").ok()?;
+        let age = Utc::now().naive_utc() - date_time;
+        Some(age.num_seconds().max(0) as u64)
+    }
+
+    fn remove(&self, run: &PipelineModel, reason: &str) -> PruneAction {
+        PruneAction::RemoveRun {
+            id: run.id.clone(),
+            name: run.name.clone(),
+            logs: self.logs(run),
+            reason: reason.to_string(),
+        }
+    }
+
+    fn workspaces(&self, max_age: u64, active: &HashSet<String>) -> Result<Vec<PruneAction>> {
+        let mut actions = Vec::new();
+        let tmp_dir = path![std::env::current_dir()?, LOCAL_MACHINE_TMP_DIR];
+        if !tmp_dir.is_dir() {
+            return Ok(actions);
+        }
+        let max_age = Duration::from_secs(max_age);
+        for entry in fs::read_dir(tmp_dir)? {
+            let entry = entry?;
+            let name = entry.file_name().to_string_lossy().to_string();
+            if active.iter().any(|id| name.starts_with(id.as_str())) {
+                continue;
+            }
+            let elapsed = entry
+                .metadata()?
+                .modified()
+                .ok()
+                .and_then(|m| SystemTime::now().duration_since(m).ok());
+            if let Some(elapsed) = elapsed {
+                if elapsed > max_age {
+                    let path = entry.path().display().to_string();
+                    actions.push(PruneAction::RemoveWorkspace { path });
+                }
+            }
+        }
+        Ok(actions)
+    }
+
+    pub fn plan(&self) -> Result<Vec<PruneAction>> {
+        let db = Database::connect(&self.config.local.db)?;
+        let (active, mut runs): (Vec<PipelineModel>, Vec<PipelineModel>) = db
+            .all()?
+            .into_iter()
+            .partition(|r| r.running || r.is_queued());
+        let active: HashSet<String> = active.into_iter().map(|r| r.id).collect();
+        runs.sort_by(|a, b| b.start_date_time.cmp(&a.start_date_time));
+
+        let mut actions = Vec::new();
+        let mut removed = HashSet::new();
+
+        if let Some(max_age) = self.retention.max_age {
+            for run in runs.iter() {
+                if Retention::age(run).map(|a| a > max_age).unwrap_or(false) {
+                    actions.push(self.remove(run, "older than max-age"));
+                    removed.insert(run.id.clone());
+                }
+            }
+        }
+
+        if let Some(max_runs) = self.retention.max_runs_per_pipeline {
+            let mut counts = HashMap::new();
+            for run in runs.iter().filter(|r| !removed.contains(&r.id)) {
+                let count = counts.entry(run.name.clone()).or_insert(0);
+                *count += 1;
+                if *count > max_runs {
+                    actions.push(self.remove(run, "exceeds max-runs-per-pipeline"));
+                }
+            }
+            for action in actions.iter() {
+                if let PruneAction::RemoveRun { id, .. } = action {
+                    removed.insert(id.clone());
+                }
+            }
+        }
+
+        if let Some(max_size) = self.retention.max_total_size {
+            let mut total: u64 = runs
+                .iter()
+                .filter(|r| !removed.contains(&r.id))
+                .map(|r| self.size(r))
+                .sum();
+            for run in runs.iter().rev().filter(|r| !removed.contains(&r.id)) {
+                if total <= max_size {
+                    break;
+                }
+                total -= self.size(run);
+                actions.push(self.remove(run, "exceeds max-total-size"));
+            }
+            for action in actions.iter() {
+                if let PruneAction::RemoveRun { id, .. } = action {
+                    removed.insert(id.clone());
+                }
+            }
+        }
+
+        if self.retention.compress_logs {
+            for run in runs.iter().filter(|r| !removed.contains(&r.id)) {
+                let logs = self.logs(run);
+                if Path::new(&logs).is_file() {
+                    actions.push(PruneAction::CompressLog {
+                        id: run.id.clone(),
+                        logs,
+                    });
+                }
+            }
+        }
+
+        if let Some(max_age) = self.retention.max_age {
+            actions.extend(self.workspaces(max_age, &active)?);
+        }
+
+        Ok(actions)
+    }
+
+    fn compress(logs: &str) -> Result<()> {
+        let gz = format!("{}.gz", logs);
+        let mut input = File::open(logs)?;
+        let mut encoder = GzEncoder::new(File::create(&gz)?, Compression::default());
+        io::copy(&mut input, &mut encoder)?;
+        encoder.finish()?;
+        fs::remove_file(logs)?;
+        Ok(())
+    }
+
+    pub fn apply(&self, actions: &[PruneAction]) -> Result<()> {
+        let db = Database::connect(&self.config.local.db)?;
+        for action in actions.iter() {
+            match action {
+                PruneAction::RemoveRun { id, name, logs, .. } => {
+                    db.remove(id)?;
+                    let mut paths = vec![
+                        logs.to_string(),
+                        format!("{}.gz", logs),
+                        LogIndex::path(logs),
+                    ];
+                    paths.extend(self.sinks(name, id));
+                    for path in paths.iter() {
+                        if Path::new(path).is_file() {
+                            fs::remove_file(path)?;
+                        }
+                    }
+                }
+                PruneAction::CompressLog { logs, .. } => Retention::compress(logs)?,
+                PruneAction::RemoveWorkspace { path } => fs::remove_dir_all(path)?,
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,57 @@
+use crate::persist::Logger;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+pub type RingBuffer = Arc<Mutex<VecDeque<String>>>;
+
+pub struct RingLogger {
+    capacity: usize,
+    lines: RingBuffer,
+}
+
+impl RingLogger {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            lines: Arc::new(Mutex::new(VecDeque::with_capacity(capacity))),
+        }
+    }
+
+    pub fn buffer(&self) -> RingBuffer {
+        self.lines.clone()
+    }
+
+    fn push(&mut self, text: &str) {
+        let mut lines = self.lines.lock().unwrap();
+        for line in text.lines() {
+            if lines.len() == self.capacity {
+                lines.pop_front();
+            }
+            lines.push_back(line.to_string());
+        }
+    }
+}
+
+impl Logger for RingLogger {
+    fn step(&mut self, _name: Option<&str>) {}
+
+    fn dumpln(&mut self, text: &str) {
+        self.push(text);
+    }
+
+    fn stdout(&mut self, line: &str) {
+        self.push(line);
+    }
+
+    fn stderr(&mut self, line: &str) {
+        self.push(line);
+    }
+
+    fn info(&mut self, text: &str) {
+        self.push(text);
+    }
+
+    fn error(&mut self, text: &str) {
+        self.push(text);
+    }
+}
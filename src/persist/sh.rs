@@ -1,25 +1,24 @@
 use crate::persist::Logger;
 use std::io::Write;
-use std::sync::{Arc, Mutex};
 use termcolor::{Color, ColorChoice, ColorSpec, StandardStream, WriteColor};
 
 pub struct ShellLogger;
 
-impl ShellLogger {
-    pub fn atom() -> Arc<Mutex<Self>> {
-        Arc::new(Mutex::new(Self))
-    }
-}
-
 impl Logger for ShellLogger {
-    fn dump(&mut self, text: &str) {
-        print!("{}", text);
-    }
+    fn step(&mut self, _name: Option<&str>) {}
 
     fn dumpln(&mut self, text: &str) {
         println!("{}", text);
     }
 
+    fn stdout(&mut self, line: &str) {
+        println!("{}", line);
+    }
+
+    fn stderr(&mut self, line: &str) {
+        eprintln!("{}", line);
+    }
+
     fn info(&mut self, text: &str) {
         let mut stdout = StandardStream::stdout(ColorChoice::Always);
         let _ = stdout.set_color(ColorSpec::new().set_fg(Some(Color::Green)));
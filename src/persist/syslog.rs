@@ -0,0 +1,83 @@
+use crate::persist::Logger;
+use crate::types::{BldError, Result};
+
+const SYSLOG_FACILITY_USER: u8 = 1;
+const SYSLOG_ERROR: u8 = 3;
+const SYSLOG_WARNING: u8 = 4;
+const SYSLOG_NOTICE: u8 = 5;
+const SYSLOG_INFO: u8 = 6;
+
+pub struct SyslogLogger {
+    #[cfg(unix)]
+    socket: std::os::unix::net::UnixDatagram,
+    tag: String,
+    step: Option<String>,
+}
+
+impl SyslogLogger {
+    #[cfg(unix)]
+    pub fn connect(path: &str, tag: &str) -> Result<Self> {
+        let socket = std::os::unix::net::UnixDatagram::unbound()?;
+        socket.connect(path).map_err(|e| {
+            BldError::IoError(format!("couldn't connect to syslog at {}: {}", path, e))
+        })?;
+        Ok(Self {
+            socket,
+            tag: tag.to_string(),
+            step: None,
+        })
+    }
+
+    #[cfg(not(unix))]
+    pub fn connect(_path: &str, _tag: &str) -> Result<Self> {
+        let message = String::from("syslog is not supported on this platform");
+        Err(BldError::Other(message))
+    }
+
+    #[cfg(unix)]
+    fn write(&self, message: &str) {
+        if let Err(e) = self.socket.send(message.as_bytes()) {
+            eprintln!("Couldn't write to syslog: {}", e);
+        }
+    }
+
+    #[cfg(not(unix))]
+    fn write(&self, _message: &str) {}
+
+    fn send(&mut self, severity: u8, text: &str) {
+        let priority = SYSLOG_FACILITY_USER * 8 + severity;
+        let mut prefix = format!("<{}>bld[{}]: [{}]", priority, std::process::id(), self.tag);
+        if let Some(step) = &self.step {
+            prefix.push_str(&format!(" [{}]", step));
+        }
+        for line in text.lines() {
+            self.write(&format!("{} {}", prefix, line));
+        }
+    }
+}
+
+impl Logger for SyslogLogger {
+    fn step(&mut self, name: Option<&str>) {
+        self.step = name.map(|n| n.to_string());
+    }
+
+    fn dumpln(&mut self, text: &str) {
+        self.send(SYSLOG_INFO, text);
+    }
+
+    fn stdout(&mut self, line: &str) {
+        self.send(SYSLOG_INFO, line);
+    }
+
+    fn stderr(&mut self, line: &str) {
+        self.send(SYSLOG_WARNING, line);
+    }
+
+    fn info(&mut self, text: &str) {
+        self.send(SYSLOG_NOTICE, text);
+    }
+
+    fn error(&mut self, text: &str) {
+        self.send(SYSLOG_ERROR, text);
+    }
+}
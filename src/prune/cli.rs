@@ -0,0 +1,12 @@
+use crate::config::definitions::VERSION;
+use clap::{App, Arg, SubCommand};
+
+pub fn command() -> App<'static, 'static> {
+    let dry_run = Arg::with_name("dry-run")
+        .long("dry-run")
+        .help("Prints the runs, logs and workspaces that would be pruned without changing them");
+    SubCommand::with_name("prune")
+        .about("Applies the retention settings of the local config to the stored runs, logs and workspaces")
+        .version(VERSION)
+        .args(&[dry_run])
+}
@@ -0,0 +1,22 @@
+use crate::config::BldConfig;
+use crate::helpers::term;
+use crate::persist::Retention;
+use crate::types::Result;
+use clap::ArgMatches;
+
+pub fn exec(matches: &ArgMatches<'_>) -> Result<()> {
+    let config = BldConfig::load()?;
+    let retention = Retention::new(&config)?;
+    let actions = retention.plan()?;
+    if actions.is_empty() {
+        return term::print_info("nothing to prune");
+    }
+    for action in actions.iter() {
+        println!("{}", action.to_string());
+    }
+    if matches.is_present("dry-run") {
+        return Ok(());
+    }
+    retention.apply(&actions)?;
+    term::print_info(&format!("applied {} actions", actions.len()))
+}
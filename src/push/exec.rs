@@ -5,23 +5,6 @@ use crate::helpers::term::print_error;
 use crate::run::Pipeline;
 use crate::types::{PushInfo, Result};
 use clap::ArgMatches;
-use std::collections::HashSet;
-
-fn build_payload(name: String) -> Result<HashSet<(String, String)>> {
-    let src = Pipeline::read(&name)?;
-    let pipeline = Pipeline::parse(&src)?;
-    let mut set = HashSet::new();
-    set.insert((name, src));
-    for step in pipeline.steps.iter() {
-        if let Some(pipeline) = &step.call {
-            let subset = build_payload(pipeline.to_string())?;
-            for entry in subset {
-                set.insert(entry);
-            }
-        }
-    }
-    Ok(set)
-}
 
 pub fn exec(matches: &ArgMatches<'_>) -> Result<()> {
     let config = BldConfig::load()?;
@@ -38,7 +21,7 @@ pub fn exec(matches: &ArgMatches<'_>) -> Result<()> {
         },
         None => (&srv.name, &srv.auth),
     };
-    match build_payload(pip) {
+    match Pipeline::snapshot(&pip) {
         Ok(payload) => {
             let sys = String::from("bld-push");
             let data: Vec<PushInfo> = payload
@@ -48,9 +31,9 @@ pub fn exec(matches: &ArgMatches<'_>) -> Result<()> {
                     PushInfo::new(n, s)
                 })
                 .collect();
-            let url = format!("http://{}:{}/push", srv.host, srv.port);
+            let urls = srv.urls("/push");
             let headers = headers(name, auth)?;
-            exec_post(sys, url, headers, data);
+            exec_post(sys, urls, headers, data);
         }
         Err(e) => {
             let _ = print_error(&e.to_string());
@@ -0,0 +1,19 @@
+use crate::config::definitions::VERSION;
+use clap::{App, Arg, SubCommand};
+
+pub fn command() -> App<'static, 'static> {
+    let server = Arg::with_name("server")
+        .short("s")
+        .long("server")
+        .takes_value(true)
+        .help("The name of the server from which to fetch the run queue");
+    let cancel = Arg::with_name("cancel")
+        .short("c")
+        .long("cancel")
+        .takes_value(true)
+        .help("The id of a queued pipeline run to cancel");
+    SubCommand::with_name("queue")
+        .about("Lists or cancels queued pipeline runs on a server")
+        .version(VERSION)
+        .args(&[server, cancel])
+}
@@ -0,0 +1,30 @@
+use crate::config::BldConfig;
+use crate::helpers::errors::auth_for_server_invalid;
+use crate::helpers::request::{exec_get, exec_post, headers};
+use crate::types::Result;
+use clap::ArgMatches;
+
+pub fn exec(matches: &ArgMatches<'_>) -> Result<()> {
+    let config = BldConfig::load()?;
+    let srv = config.remote.server_or_first(matches.value_of("server"))?;
+    let (name, auth) = match &srv.same_auth_as {
+        Some(name) => match config.remote.servers.iter().find(|s| &s.name == name) {
+            Some(srv) => (&srv.name, &srv.auth),
+            None => return auth_for_server_invalid(),
+        },
+        None => (&srv.name, &srv.auth),
+    };
+    let sys = String::from("bld-queue");
+    let headers = headers(name, auth)?;
+    match matches.value_of("cancel") {
+        Some(id) => {
+            let urls = srv.urls("/cancel");
+            exec_post(sys, urls, headers, id.to_string());
+        }
+        None => {
+            let urls = srv.urls("/queue");
+            exec_get(sys, urls, headers);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,23 @@
+use crate::config::definitions::VERSION;
+use clap::{App, Arg, SubCommand};
+
+pub fn command() -> App<'static, 'static> {
+    let id = Arg::with_name("id")
+        .short("i")
+        .long("id")
+        .help("The id of a previous run on a server")
+        .required(true)
+        .takes_value(true);
+    let server = Arg::with_name("server")
+        .short("s")
+        .long("server")
+        .help("The name of the server that the pipeline was run")
+        .takes_value(true);
+    let latest_pipeline = Arg::with_name("latest-pipeline")
+        .long("latest-pipeline")
+        .help("Use the current pipeline files instead of the ones recorded for the run");
+    SubCommand::with_name("rerun")
+        .about("Runs a pipeline again on a server with the inputs of a previous run")
+        .version(VERSION)
+        .args(&[id, server, latest_pipeline])
+}
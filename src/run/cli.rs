@@ -7,6 +7,12 @@ pub fn command() -> App<'static, 'static> {
         .long("pipeline")
         .help("Path to pipeline script")
         .takes_value(true);
+    let file = Arg::with_name("file")
+        .short("f")
+        .long("file")
+        .help("Path to a pipeline file outside of the .bld directory")
+        .conflicts_with("pipeline")
+        .takes_value(true);
     let server = Arg::with_name("server")
         .short("s")
         .long("server")
@@ -22,8 +28,26 @@ pub fn command() -> App<'static, 'static> {
         .help("Define values for variables of a pipeline")
         .multiple(true)
         .takes_value(true);
+    let priority = Arg::with_name("priority")
+        .long("priority")
+        .help("The priority of the run in the server queue, higher values run first")
+        .takes_value(true);
+    let dry_run = Arg::with_name("dry-run").long("dry-run").help(
+        "Prints the resolved steps, commands and artifacts of the pipeline without running it",
+    );
+    let record = Arg::with_name("record")
+        .long("record")
+        .help("Records the run of a local pipeline in the history database and writes its output to a log file");
+    let log_sink = Arg::with_name("log-sink")
+        .long("log-sink")
+        .help("The sinks to write the output of a local run to, one of terminal, file, json, syslog and ring")
+        .takes_value(true)
+        .multiple(true)
+        .number_of_values(1);
     SubCommand::with_name("run")
         .about("Executes a build pipeline")
         .version(VERSION)
-        .args(&[pipeline, server, detach, variables])
+        .args(&[
+            pipeline, file, server, detach, variables, priority, dry_run, record, log_sink,
+        ])
 }
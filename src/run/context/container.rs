@@ -1,26 +1,31 @@
 use crate::config::BldConfig;
 use crate::persist::Logger;
-use crate::types::{BldError, CheckStopSignal, Result};
+use crate::run::{PullPolicy, Shell};
+use crate::types::{stop_signal, BldError, Result, StopReceiver};
 use futures::TryStreamExt;
 use futures_util::StreamExt;
+use serde_json::Value;
 use shiplift::tty::TtyChunk;
-use shiplift::{ContainerOptions, Docker, ExecContainerOptions, ImageListOptions, PullOptions};
+use shiplift::{
+    ContainerOptions, Docker, Exec, ExecContainerOptions, ImageListOptions, PullOptions,
+};
+use std::collections::HashMap;
 use std::path::Path;
-use std::rc::Rc;
-use std::sync::mpsc::Receiver;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
-use std::thread::sleep;
 use std::time::Duration;
 use tar::Archive;
+use tokio::time::{self, Instant};
 
-type AtomicRecv = Arc<Mutex<Receiver<bool>>>;
+static DOCKER_TLS_READY: AtomicBool = AtomicBool::new(false);
 
 pub struct Container {
-    pub config: Option<Rc<BldConfig>>,
+    pub config: Option<Arc<BldConfig>>,
     pub img: String,
     pub client: Option<Docker>,
     pub id: Option<String>,
-    pub lg: Arc<Mutex<dyn Logger>>,
+    pub lg: Arc<Mutex<dyn Logger + Send>>,
+    pub pull_time: Duration,
 }
 
 impl Container {
@@ -38,55 +43,131 @@ impl Container {
         }
     }
 
-    fn docker(config: &Rc<BldConfig>) -> Result<Docker> {
-        let url = config.local.docker_url.parse()?;
-        let host = Docker::host(url);
+    fn docker(config: &BldConfig) -> Result<Docker> {
+        let url = &config.local.docker_url;
+        if let Some(socket) = url.strip_prefix("unix://") {
+            return Ok(Docker::unix(socket));
+        }
+        if let Some(tls) = &config.local.docker_tls {
+            if !DOCKER_TLS_READY.load(Ordering::SeqCst) {
+                tls.apply_env()?;
+                DOCKER_TLS_READY.store(true, Ordering::SeqCst);
+            }
+        }
+        let host = Docker::host(url.parse()?);
         Ok(host)
     }
 
-    async fn pull(client: &Docker, image: &str, logger: &mut Arc<Mutex<dyn Logger>>) -> Result<()> {
+    fn image_tag(image: &str) -> Option<&str> {
+        let name = image.rsplit('/').next().unwrap_or(image);
+        if name.contains(':') || name.contains('@') {
+            None
+        } else {
+            Some("latest")
+        }
+    }
+
+    async fn image_exists(client: &Docker, image: &str) -> Result<bool> {
         let options = ImageListOptions::builder().filter_name(image).build();
         let images = client.images().list(&options).await?;
-        if images.is_empty() {
-            {
-                let mut logger = logger.lock().unwrap();
-                logger.info(&format!("Download image: {}", image));
-            }
-            let options = PullOptions::builder().image(image).build();
-            let mut pull_iter = client.images().pull(&options);
-            while let Some(progress) = pull_iter.next().await {
-                let info = progress?;
-                {
-                    let mut logger = logger.lock().unwrap();
-                    logger.dumpln(&info.to_string());
+        Ok(!images.is_empty())
+    }
+
+    fn pull_progress(layers: &mut HashMap<String, String>, info: &Value) -> Option<String> {
+        let status = info["status"].as_str()?;
+        match info["id"].as_str() {
+            Some(id) => {
+                if layers.get(id).map(|s| s == status).unwrap_or(false) {
+                    return None;
                 }
-                sleep(Duration::from_millis(100));
+                layers.insert(id.to_string(), status.to_string());
+                Some(format!("{}: {}", id, status))
             }
+            None => Some(status.to_string()),
         }
-        Ok(())
     }
 
-    async fn create(
+    async fn pull(
         client: &Docker,
         image: &str,
-        logger: &mut Arc<Mutex<dyn Logger>>,
-    ) -> Result<String> {
-        Container::pull(client, image, logger).await?;
+        policy: &PullPolicy,
+        config: &BldConfig,
+        logger: &mut Arc<Mutex<dyn Logger + Send>>,
+    ) -> Result<()> {
+        let should_pull = match policy {
+            PullPolicy::Always => true,
+            PullPolicy::IfNotPresent => !Container::image_exists(client, image).await?,
+            PullPolicy::Never => {
+                if !Container::image_exists(client, image).await? {
+                    let message = format!("image {} not found and pull policy is never", image);
+                    return Err(BldError::Other(message));
+                }
+                false
+            }
+        };
+        if !should_pull {
+            return Ok(());
+        }
+        {
+            let mut logger = logger.lock().unwrap();
+            logger.info(&format!("Download image: {}", image));
+        }
+        let mut options = PullOptions::builder();
+        options.image(image);
+        if let Some(tag) = Container::image_tag(image) {
+            options.tag(tag);
+        }
+        if let Some(registry) = config.local.registry(image) {
+            options.auth(registry.auth());
+        }
+        let options = options.build();
+        let mut layers = HashMap::new();
+        let mut pull_iter = client.images().pull(&options);
+        while let Some(progress) = pull_iter.next().await {
+            let info = progress?;
+            if let Some(message) = info["error"].as_str() {
+                return Err(BldError::ShipliftError(message.to_string()));
+            }
+            if let Some(line) = Container::pull_progress(&mut layers, &info) {
+                let mut logger = logger.lock().unwrap();
+                logger.dumpln(&line);
+            }
+        }
+        Ok(())
+    }
+
+    async fn create(client: &Docker, image: &str) -> Result<String> {
         let options = ContainerOptions::builder(&image).tty(true).build();
         let info = client.containers().create(&options).await?;
         client.containers().get(&info.id).start().await?;
         Ok(info.id)
     }
 
-    pub async fn new(img: &str, cfg: Rc<BldConfig>, lg: Arc<Mutex<dyn Logger>>) -> Result<Self> {
+    pub async fn new(
+        img: &str,
+        pull: &PullPolicy,
+        cfg: Arc<BldConfig>,
+        lg: Arc<Mutex<dyn Logger + Send>>,
+    ) -> Result<Self> {
         let client = Container::docker(&cfg)?;
-        let id = Container::create(&client, &img, &mut lg.clone()).await?;
+        if cfg.local.docker_url.starts_with("tcp://") && cfg.local.docker_tls.is_none() {
+            let mut logger = lg.lock().unwrap();
+            logger.error(&format!(
+                "Warning: connecting to docker at {} without tls, configure docker-tls to secure the connection",
+                cfg.local.docker_url
+            ));
+        }
+        let started = Instant::now();
+        Container::pull(&client, img, pull, &cfg, &mut lg.clone()).await?;
+        let pull_time = started.elapsed();
+        let id = Container::create(&client, &img).await?;
         Ok(Self {
             config: Some(cfg),
             img: img.to_string(),
             client: Some(client),
             id: Some(id),
             lg,
+            pull_time,
         })
     }
 
@@ -109,48 +190,156 @@ impl Container {
         Ok(())
     }
 
-    pub async fn sh(
-        &self,
+    fn lines(buffer: &mut Vec<u8>, bytes: &[u8]) -> Vec<String> {
+        buffer.extend_from_slice(bytes);
+        let mut lines = Vec::new();
+        while let Some(index) = buffer.iter().position(|b| *b == b'\n') {
+            let line: Vec<u8> = buffer.drain(..=index).collect();
+            let line = String::from_utf8_lossy(&line[..line.len() - 1]);
+            lines.push(line.trim_end_matches('\r').to_string());
+        }
+        lines
+    }
+
+    fn exec_options(
         working_dir: &Option<String>,
+        shell: &Option<Shell>,
         input: &str,
-        cm: &Option<AtomicRecv>,
-    ) -> Result<()> {
+    ) -> ExecContainerOptions {
+        let mut cmd = Vec::<String>::new();
+        if let Some(wd) = working_dir {
+            cmd.push("sh".to_string());
+            cmd.push("-c".to_string());
+            cmd.push("cd \"$0\" && exec \"$@\"".to_string());
+            cmd.push(wd.to_string());
+        }
+        cmd.extend(shell.as_ref().unwrap_or(&Shell::Sh).command(input));
+        ExecContainerOptions::builder()
+            .cmd(cmd.iter().map(|c| c.as_str()).collect())
+            .attach_stdout(true)
+            .attach_stderr(true)
+            .build()
+    }
+
+    fn grace_period(&self) -> Duration {
+        let seconds = self
+            .config
+            .as_ref()
+            .map(|c| c.local.stop_grace_period)
+            .unwrap_or(0);
+        Duration::from_secs(seconds)
+    }
+
+    async fn signal(&self, signal: &str) -> Result<()> {
         let client = self.get_client()?;
         let id = self.get_id()?;
-        let input = working_dir
-            .as_ref()
-            .map(|wd| format!("cd {} && {}", &wd, input))
-            .or_else(|| Some(input.to_string()))
-            .unwrap();
-        let cmd = vec!["bash", "-c", &input];
         let options = ExecContainerOptions::builder()
-            .cmd(cmd)
+            .cmd(vec!["sh", "-c", &format!("kill -s {} -1", signal)])
             .attach_stdout(true)
             .attach_stderr(true)
             .build();
-        let container = client.containers().get(&id);
-        let mut exec_iter = container.exec(&options);
+        let exec = Exec::create(client, id, &options).await?;
+        let mut exec_iter = exec.start();
+        while exec_iter.next().await.is_some() {}
+        Ok(())
+    }
+
+    async fn exit_code(exec: &Exec<'_>) -> Result<()> {
+        match exec.inspect().await?.exit_code {
+            Some(0) | None => Ok(()),
+            Some(code) => {
+                let message = format!("command exited with exit code: {}", code);
+                Err(BldError::Other(message))
+            }
+        }
+    }
+
+    pub async fn sh(
+        &self,
+        working_dir: &Option<String>,
+        shell: &Option<Shell>,
+        input: &str,
+        cm: &Option<StopReceiver>,
+    ) -> Result<()> {
+        let client = self.get_client()?;
+        let id = self.get_id()?;
+        let options = Container::exec_options(working_dir, shell, input);
+        let exec = Exec::create(client, id, &options).await?;
+        let mut exec_iter = exec.start();
+        let mut stdout = Vec::<u8>::new();
+        let mut stderr = Vec::<u8>::new();
+        let mut deadline: Option<Instant> = None;
+        let stop = stop_signal(cm);
+        tokio::pin!(stop);
+        loop {
+            tokio::select! {
+                result = exec_iter.next() => match result {
+                    Some(Ok(TtyChunk::StdOut(bytes))) => {
+                        for line in Container::lines(&mut stdout, &bytes) {
+                            self.lg.lock().unwrap().stdout(&line);
+                        }
+                    }
+                    Some(Ok(TtyChunk::StdErr(bytes))) => {
+                        for line in Container::lines(&mut stderr, &bytes) {
+                            self.lg.lock().unwrap().stderr(&line);
+                        }
+                    }
+                    Some(Ok(TtyChunk::StdIn(_))) => unreachable!(),
+                    Some(Err(e)) => return Err(BldError::ShipliftError(e.to_string())),
+                    None => break,
+                },
+                _ = &mut stop, if deadline.is_none() => {
+                    self.signal("TERM").await?;
+                    deadline = Some(Instant::now() + self.grace_period());
+                }
+                _ = time::delay_until(deadline.unwrap_or_else(Instant::now)), if deadline.is_some() => {
+                    self.signal("KILL").await?;
+                    break;
+                }
+            }
+        }
+        if deadline.is_some() {
+            return Err(BldError::Stopped);
+        }
+        {
+            let mut logger = self.lg.lock().unwrap();
+            if !stdout.is_empty() {
+                logger.stdout(&String::from_utf8_lossy(&stdout));
+            }
+            if !stderr.is_empty() {
+                logger.stderr(&String::from_utf8_lossy(&stderr));
+            }
+        }
+        Container::exit_code(&exec).await
+    }
+
+    pub async fn capture(
+        &self,
+        working_dir: &Option<String>,
+        shell: &Option<Shell>,
+        input: &str,
+    ) -> Result<String> {
+        let client = self.get_client()?;
+        let id = self.get_id()?;
+        let options = Container::exec_options(working_dir, shell, input);
+        let exec = Exec::create(client, id, &options).await?;
+        let mut exec_iter = exec.start();
+        let mut stdout = Vec::<u8>::new();
         while let Some(result) = exec_iter.next().await {
-            cm.check_stop_signal()?;
-            let chunk = match result {
-                Ok(TtyChunk::StdOut(bytes)) => String::from_utf8(bytes).unwrap(),
-                Ok(TtyChunk::StdErr(bytes)) => String::from_utf8(bytes).unwrap(),
-                Ok(TtyChunk::StdIn(_)) => unreachable!(),
-                Err(e) => return Err(BldError::ShipliftError(e.to_string())),
-            };
-            {
-                let mut logger = self.lg.lock().unwrap();
-                logger.dump(&chunk);
+            match result? {
+                TtyChunk::StdOut(bytes) => stdout.extend_from_slice(&bytes),
+                TtyChunk::StdErr(_) => {}
+                TtyChunk::StdIn(_) => unreachable!(),
             }
-            sleep(Duration::from_millis(100));
         }
-        Ok(())
+        Container::exit_code(&exec).await?;
+        Ok(String::from_utf8_lossy(&stdout).trim().to_string())
     }
 
     pub async fn dispose(&self) -> Result<()> {
         let client = self.get_client()?;
         let id = self.get_id()?;
-        client.containers().get(id).stop(None).await?;
+        let _ = client.containers().get(id).stop(None).await;
         client.containers().get(id).delete().await?;
         Ok(())
     }
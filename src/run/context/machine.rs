@@ -1,35 +1,45 @@
 use crate::config::definitions::LOCAL_MACHINE_TMP_DIR;
+use crate::config::BldConfig;
 use crate::os::{self, OSname};
 use crate::path;
 use crate::persist::Logger;
-use crate::types::{BldError, Result};
+use crate::run::Shell;
+use crate::types::{stop_signal, BldError, Result, StopReceiver};
 use std::path::{Path, PathBuf};
-use std::process::Command;
+use std::process::Stdio;
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, BufReader};
+use tokio::process::{Child, Command};
+use tokio::time::{self, Instant};
 use uuid::Uuid;
 
-fn could_not_spawn_shell() -> Result<()> {
-    let message = String::from("could not spawn shell");
-    Err(BldError::Other(message))
-}
-
 pub struct Machine {
     tmp_dir: String,
-    lg: Arc<Mutex<dyn Logger>>,
+    grace_period: Duration,
+    lg: Arc<Mutex<dyn Logger + Send>>,
 }
 
 impl Machine {
-    pub fn new(lg: Arc<Mutex<dyn Logger>>) -> Result<Self> {
-        let tmp_path = path![
-            std::env::current_dir()?,
-            LOCAL_MACHINE_TMP_DIR,
-            Uuid::new_v4().to_string()
-        ];
+    pub fn new(
+        cfg: &BldConfig,
+        run: Option<&str>,
+        lg: Arc<Mutex<dyn Logger + Send>>,
+    ) -> Result<Self> {
+        let workspace = match run {
+            Some(run) => format!("{}-{}", run, Uuid::new_v4()),
+            None => Uuid::new_v4().to_string(),
+        };
+        let tmp_path = path![std::env::current_dir()?, LOCAL_MACHINE_TMP_DIR, workspace];
         let tmp_dir = tmp_path.display().to_string();
         if !tmp_path.is_dir() {
             std::fs::create_dir_all(tmp_path)?;
         }
-        Ok(Self { tmp_dir, lg })
+        Ok(Self {
+            tmp_dir,
+            grace_period: Duration::from_secs(cfg.local.stop_grace_period),
+            lg,
+        })
     }
 
     fn copy(&self, from: &str, to: &str) -> Result<()> {
@@ -45,9 +55,20 @@ impl Machine {
         self.copy(from, to)
     }
 
-    pub fn sh(&self, working_dir: &Option<String>, input: &str) -> Result<()> {
-        let mut logger = self.lg.lock().unwrap();
-        let os_name = os::name();
+    pub fn default_shell() -> Shell {
+        match os::name() {
+            OSname::Windows => Shell::Powershell,
+            OSname::Linux => Shell::Bash,
+            OSname::Mac | OSname::Unknown => Shell::Sh,
+        }
+    }
+
+    fn command(
+        &self,
+        working_dir: &Option<String>,
+        shell: &Option<Shell>,
+        input: &str,
+    ) -> Result<Command> {
         let current_dir = working_dir
             .as_ref()
             .or(Some(&self.tmp_dir))
@@ -58,26 +79,120 @@ impl Machine {
         } else {
             current_dir
         };
-        let (shell, mut args) = match &os_name {
-            OSname::Windows => ("powershell.exe", Vec::<&str>::new()),
-            OSname::Linux => ("bash", vec!["-c"]),
-            OSname::Mac => ("sh", vec!["-c"]),
-            OSname::Unknown => return could_not_spawn_shell(),
-        };
-        args.push(input);
+        let args = shell
+            .as_ref()
+            .cloned()
+            .unwrap_or_else(Machine::default_shell)
+            .command(input);
 
-        let mut command = Command::new(shell);
-        command.args(&args);
+        let mut command = Command::new(&args[0]);
+        command.args(&args[1..]);
         command.current_dir(current_dir);
+        command.stdout(Stdio::piped());
+        command.stderr(Stdio::piped());
+        #[cfg(unix)]
+        unsafe {
+            command.pre_exec(|| match libc::setpgid(0, 0) {
+                0 => Ok(()),
+                _ => Err(std::io::Error::last_os_error()),
+            });
+        }
+        Ok(command)
+    }
 
-        let process = command.output()?;
-        let mut output = String::from_utf8_lossy(&process.stderr).to_string();
-        output.push_str(&format!("\r\n{}", String::from_utf8_lossy(&process.stdout)));
-        logger.dump(&output);
+    #[cfg(unix)]
+    fn signal(child: &mut Child, force: bool) {
+        let signal = if force { libc::SIGKILL } else { libc::SIGTERM };
+        unsafe {
+            libc::kill(-(child.id() as libc::pid_t), signal);
+        }
+    }
+
+    #[cfg(not(unix))]
+    fn signal(child: &mut Child, _force: bool) {
+        let _ = child.kill();
+    }
+
+    async fn next_line<R>(reader: &mut R, buffer: &mut Vec<u8>) -> Result<Option<String>>
+    where
+        R: AsyncBufRead + Unpin,
+    {
+        let read = reader.read_until(b'\n', buffer).await?;
+        if read == 0 && buffer.is_empty() {
+            return Ok(None);
+        }
+        let line = String::from_utf8_lossy(buffer)
+            .trim_end_matches(&['\n', '\r'][..])
+            .to_string();
+        buffer.clear();
+        Ok(Some(line))
+    }
+
+    pub async fn sh(
+        &self,
+        working_dir: &Option<String>,
+        shell: &Option<Shell>,
+        input: &str,
+        cm: &Option<StopReceiver>,
+    ) -> Result<()> {
+        let mut command = self.command(working_dir, shell, input)?;
+        let mut child = command.spawn()?;
+        let mut stdout = BufReader::new(child.stdout.take().unwrap());
+        let mut stderr = BufReader::new(child.stderr.take().unwrap());
+        let mut stdout_buffer = Vec::new();
+        let mut stderr_buffer = Vec::new();
+        let mut stdout_open = true;
+        let mut stderr_open = true;
+        let mut deadline: Option<Instant> = None;
+        let stop = stop_signal(cm);
+        tokio::pin!(stop);
+        while stdout_open || stderr_open {
+            tokio::select! {
+                line = Machine::next_line(&mut stdout, &mut stdout_buffer), if stdout_open => match line? {
+                    Some(line) => self.lg.lock().unwrap().stdout(&line),
+                    None => stdout_open = false,
+                },
+                line = Machine::next_line(&mut stderr, &mut stderr_buffer), if stderr_open => match line? {
+                    Some(line) => self.lg.lock().unwrap().stderr(&line),
+                    None => stderr_open = false,
+                },
+                _ = &mut stop, if deadline.is_none() => {
+                    Machine::signal(&mut child, false);
+                    deadline = Some(Instant::now() + self.grace_period);
+                }
+                _ = time::delay_until(deadline.unwrap_or_else(Instant::now)), if deadline.is_some() => {
+                    Machine::signal(&mut child, true);
+                    break;
+                }
+            }
+        }
+        let status = child.await?;
+        if deadline.is_some() {
+            return Err(BldError::Stopped);
+        }
+        if !status.success() {
+            let message = format!("command exited with {}", status);
+            return Err(BldError::Other(message));
+        }
 
         Ok(())
     }
 
+    pub async fn capture(
+        &self,
+        working_dir: &Option<String>,
+        shell: &Option<Shell>,
+        input: &str,
+    ) -> Result<String> {
+        let mut command = self.command(working_dir, shell, input)?;
+        let output = command.output().await?;
+        if !output.status.success() {
+            let message = String::from_utf8_lossy(&output.stderr).trim().to_string();
+            return Err(BldError::Other(message));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+
     pub fn dispose(&self) -> Result<()> {
         std::fs::remove_dir_all(&self.tmp_dir)?;
         Ok(())
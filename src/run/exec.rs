@@ -1,19 +1,43 @@
-use crate::config::definitions::TOOL_DEFAULT_PIPELINE;
-use crate::persist::{NullExec, ShellLogger};
-use crate::run::{self, Runner};
-use crate::types::Result;
+use crate::config::definitions::{SYSTEM_USER, TOOL_DEFAULT_PIPELINE};
+use crate::config::{BldConfig, LogSink};
+use crate::helpers::errors::auth_for_server_invalid;
+use crate::helpers::request::{exec_post, headers};
+use crate::helpers::term::print_error;
+use crate::path;
+use crate::persist::{
+    CompositeLogger, Database, Execution, FileLogger, Logger, NullExec, RingBuffer, TrackedExec,
+};
+use crate::run::{self, Pipeline, Plan, Runner};
+use crate::types::{BldError, ExecInfo, Result, RunState, RunTrigger, StopReceiver};
 use clap::ArgMatches;
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
 use tokio::runtime::Runtime;
+use tokio::signal;
+use tokio::sync::watch;
+use uuid::Uuid;
 
 pub fn exec(matches: &ArgMatches<'_>) -> Result<()> {
-    let pipeline = matches
-        .value_of("pipeline")
-        .or(Some(TOOL_DEFAULT_PIPELINE))
-        .unwrap()
-        .to_string();
+    let (pipeline, files) = match matches.value_of("file") {
+        Some(file) => {
+            let (pipeline, files) = Pipeline::snapshot_file(file)?;
+            (pipeline, Some(files))
+        }
+        None => {
+            let pipeline = matches
+                .value_of("pipeline")
+                .or(Some(TOOL_DEFAULT_PIPELINE))
+                .unwrap()
+                .to_string();
+            (pipeline, None)
+        }
+    };
     let detach = matches.is_present("detach");
+    let priority = match matches.value_of("priority") {
+        Some(priority) => Some(priority.parse::<i64>()?),
+        None => None,
+    };
     let vars = matches
         .values_of("variables")
         .map(|variable| {
@@ -28,21 +52,182 @@ pub fn exec(matches: &ArgMatches<'_>) -> Result<()> {
         })
         .or_else(|| Some(HashMap::new()))
         .unwrap();
+    if matches.is_present("dry-run") {
+        return match matches.value_of("server") {
+            Some(server) => {
+                let mut info = ExecInfo::new(&pipeline, Some(vars), None);
+                info.files = files;
+                plan_on_server(info, server)
+            }
+            None => {
+                println!("{}", Plan::from_file(&pipeline, &vars, files.as_ref())?);
+                Ok(())
+            }
+        };
+    }
     match matches.value_of("server") {
-        Some(server) => run::on_server(pipeline, vars, server.to_string(), detach),
+        Some(server) => {
+            let mut info = ExecInfo::new(&pipeline, Some(vars), priority);
+            info.files = files;
+            run::on_server(info, server.to_string(), detach)
+        }
         None => {
+            let config = BldConfig::load()?;
+            let sinks = match matches.values_of("log-sink") {
+                Some(sinks) => sinks
+                    .map(LogSink::parse)
+                    .collect::<Result<Vec<LogSink>>>()?,
+                None => config.local.logging.run_sinks.clone(),
+            };
+            if matches.is_present("record") || config.local.record_runs {
+                return record(config, sinks, pipeline, vars, files);
+            }
+            let id = Uuid::new_v4().to_string();
+            let lg = CompositeLogger::new().sinks(
+                &config.local.logging,
+                &config.local.logs,
+                &sinks,
+                &pipeline,
+                &id,
+            )?;
+            let ring = lg.ring();
+            let ex = TrackedExec::atom(NullExec);
             let mut rt = Runtime::new()?;
-            rt.block_on(async {
+            let result = rt.block_on(async {
                 Runner::from_file(
                     pipeline,
-                    NullExec::atom(),
-                    ShellLogger::atom(),
-                    None,
+                    ex.clone(),
+                    lg.atom(),
+                    Some(stop_on_ctrl_c()),
                     Arc::new(vars),
+                    files.map(Arc::new),
                 )
                 .await
-                .await
-            })
+            });
+            print_ring(ring, ex.lock().unwrap().state(), &result);
+            result
+        }
+    }
+}
+
+fn stop_on_ctrl_c() -> StopReceiver {
+    let (tx, rx) = watch::channel(false);
+    tokio::spawn(async move {
+        if signal::ctrl_c().await.is_ok() {
+            let _ = tx.broadcast(true);
+        }
+    });
+    rx
+}
+
+fn print_ring(ring: Option<RingBuffer>, state: Option<RunState>, result: &Result<()>) {
+    let failed = match result {
+        Ok(_) => state == Some(RunState::Failed),
+        Err(BldError::Stopped) => false,
+        Err(_) => true,
+    };
+    if let (Some(ring), true) = (ring, failed) {
+        let lines = ring.lock().unwrap();
+        let _ = print_error(&format!("Last {} lines of the run:", lines.len()));
+        for line in lines.iter() {
+            eprintln!("{}", line);
+        }
+    }
+}
+
+fn plan_on_server(info: ExecInfo, server: &str) -> Result<()> {
+    let config = BldConfig::load()?;
+    let srv = config.remote.server(server)?;
+    let (name, auth) = match &srv.same_auth_as {
+        Some(name) => match config.remote.servers.iter().find(|s| &s.name == name) {
+            Some(srv) => (&srv.name, &srv.auth),
+            None => return auth_for_server_invalid(),
+        },
+        None => (&srv.name, &srv.auth),
+    };
+    let sys = String::from("bld-run");
+    let urls = srv.urls("/plan");
+    let headers = headers(name, auth)?;
+    exec_post(sys, urls, headers, info);
+    Ok(())
+}
+
+fn record(
+    config: BldConfig,
+    sinks: Vec<LogSink>,
+    pipeline: String,
+    vars: HashMap<String, String>,
+    files: Option<HashMap<String, String>>,
+) -> Result<()> {
+    std::fs::create_dir_all(&config.local.db)?;
+    std::fs::create_dir_all(&config.local.logs)?;
+
+    let files = match files {
+        Some(files) => files,
+        None => Pipeline::snapshot(&pipeline)?,
+    };
+    let user = std::env::var("USER")
+        .or_else(|_| std::env::var("USERNAME"))
+        .unwrap_or_else(|_| SYSTEM_USER.to_string());
+    let id = Uuid::new_v4().to_string();
+    let logs = path![&config.local.logs, format!("{}-{}", pipeline, id)]
+        .display()
+        .to_string();
+
+    let mut db = Database::connect(&config.local.db)?;
+    db.add(&id, &pipeline, &user, &RunTrigger::Manual, None)?;
+    db.snapshot(&files, &vars)?;
+    let mut lg = CompositeLogger::new()
+        .add(FileLogger::new(&logs)?.index())
+        .sinks(
+            &config.local.logging,
+            &config.local.logs,
+            &sinks,
+            &pipeline,
+            &id,
+        )?;
+    lg.info(&format!("Recording run with id: {}", id));
+    let ring = lg.ring();
+    let ex = TrackedExec::atom(db);
+    let lg = lg.atom();
+
+    let mut rt = Runtime::new()?;
+    let result = rt.block_on(async {
+        Runner::from_file(
+            pipeline,
+            ex.clone(),
+            lg.clone(),
+            Some(stop_on_ctrl_c()),
+            Arc::new(vars),
+            Some(Arc::new(files)),
+        )
+        .await
+    });
+    if let Err(e) = &result {
+        fail(&ex, &lg, e);
+    }
+    print_ring(ring, ex.lock().unwrap().state(), &result);
+    result
+}
+
+fn fail(
+    ex: &Arc<Mutex<TrackedExec<Database>>>,
+    lg: &Arc<Mutex<CompositeLogger>>,
+    error: &BldError,
+) {
+    if let BldError::Stopped = error {
+        return;
+    }
+    let mut lg = lg.lock().unwrap();
+    lg.dumpln(&error.to_string());
+    let mut ex = ex.lock().unwrap();
+    let active = ex
+        .state()
+        .map(|s| s == RunState::Queued || s.is_running())
+        .unwrap_or(true);
+    if active {
+        if let Err(e) = ex.update(RunState::Failed) {
+            lg.error(&format!("Couldn't persist the run: {}", e.to_string()));
         }
     }
 }
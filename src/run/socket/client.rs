@@ -1,4 +1,4 @@
-use crate::types::ExecInfo;
+use crate::types::{ExecInfo, LogEntry};
 use actix::io::{SinkWrite, WriteHandler};
 use actix::{Actor, ActorContext, AsyncContext, Context, Handler, StreamHandler, System};
 use actix_codec::Framed;
@@ -51,7 +51,9 @@ impl Handler<ExecInfo> for ExecClient {
 impl StreamHandler<Result<Frame, WsProtocolError>> for ExecClient {
     fn handle(&mut self, msg: Result<Frame, WsProtocolError>, _: &mut Context<Self>) {
         match msg {
-            Ok(Frame::Text(bt)) => println!("{}", String::from_utf8_lossy(&bt[..])),
+            Ok(Frame::Text(bt)) => {
+                LogEntry::parse(&String::from_utf8_lossy(&bt[..])).print(false, false)
+            }
             Ok(Frame::Close(_)) => System::current().stop(),
             _ => {}
         }
@@ -4,14 +4,12 @@ pub use client::*;
 
 use crate::config::BldConfig;
 use crate::helpers::errors::auth_for_server_invalid;
-use crate::helpers::request::headers;
+use crate::helpers::request::{headers, ws_connect};
 use crate::helpers::term::print_error;
 use crate::run::socket::ExecClient;
 use crate::types::{ExecInfo, Result};
 use actix::{io::SinkWrite, Actor, Arbiter, StreamHandler, System};
-use awc::Client;
 use futures::stream::StreamExt;
-use std::collections::HashMap;
 
 async fn remote_invoke(server: String, detach: bool, data: ExecInfo) -> Result<bool> {
     let config = BldConfig::load()?;
@@ -23,13 +21,9 @@ async fn remote_invoke(server: String, detach: bool, data: ExecInfo) -> Result<b
         },
         None => (&srv.name, &srv.auth),
     };
-    let url = format!("http://{}:{}/ws-exec/", srv.host, srv.port);
+    let urls = srv.urls("/ws-exec/");
     let headers = headers(srv_name, auth)?;
-    let mut client = Client::new().ws(url);
-    for (key, value) in headers.iter() {
-        client = client.header(&key[..], &value[..]);
-    }
-    let (_, framed) = client.connect().await?;
+    let (_, framed) = ws_connect(urls, &headers).await?;
     let (sink, stream) = framed.split();
     let addr = ExecClient::create(|ctx| {
         ExecClient::add_stream(stream, ctx);
@@ -44,14 +38,8 @@ async fn remote_invoke(server: String, detach: bool, data: ExecInfo) -> Result<b
     }
 }
 
-pub fn on_server(
-    name: String,
-    vars: HashMap<String, String>,
-    server: String,
-    detach: bool,
-) -> Result<()> {
+pub fn on_server(data: ExecInfo, server: String, detach: bool) -> Result<()> {
     let system = System::new("bld");
-    let data = ExecInfo::new(&name, Some(vars));
     Arbiter::spawn(async move {
         match remote_invoke(server, detach, data).await {
             Ok(true) => System::current().stop(),
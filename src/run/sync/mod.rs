@@ -1,5 +1,7 @@
 mod pipeline;
+mod plan;
 mod runner;
 
 pub use pipeline::*;
+pub use plan::*;
 pub use runner::*;
@@ -1,9 +1,10 @@
-use crate::config::definitions::TOOL_DIR;
+use crate::config::definitions::{TOOL_DIR, VAR_TOKEN};
 use crate::helpers::errors::err_variable_in_yaml;
 use crate::path;
-use crate::types::{BldError, Result, EMPTY_YAML_VEC};
+use crate::types::{yaml_string_map, BldError, Result, RunState, EMPTY_YAML_VEC};
+use std::collections::HashMap;
 use std::fmt::{self, Display, Formatter};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use yaml_rust::{Yaml, YamlLoader};
 
 pub enum RunsOn {
@@ -20,6 +21,80 @@ impl Display for RunsOn {
     }
 }
 
+pub enum PullPolicy {
+    Always,
+    IfNotPresent,
+    Never,
+}
+
+impl PullPolicy {
+    pub fn load(yaml: &Yaml) -> Result<Self> {
+        match yaml["pull"].as_str() {
+            Some("always") => Ok(Self::Always),
+            Some("if-not-present") | None => Ok(Self::IfNotPresent),
+            Some("never") => Ok(Self::Never),
+            Some(policy) => Err(BldError::YamlError(format!(
+                "invalid pull policy: {}",
+                policy
+            ))),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub enum Shell {
+    Sh,
+    Bash,
+    Python3,
+    Powershell,
+    Custom(String),
+}
+
+impl Shell {
+    pub fn load(yaml: &Yaml) -> Result<Option<Self>> {
+        let shell = match yaml["shell"].as_str() {
+            Some("sh") => Self::Sh,
+            Some("bash") => Self::Bash,
+            Some("python3") => Self::Python3,
+            Some("powershell") => Self::Powershell,
+            Some(template) if template.contains("{0}") => {
+                if let Err(e) = shell_words::split(template) {
+                    let message = format!("invalid shell: {}. {}", template, e);
+                    return Err(BldError::YamlError(message));
+                }
+                Self::Custom(template.to_string())
+            }
+            Some(template) => {
+                return Err(BldError::YamlError(format!(
+                    "invalid shell: {}. a custom shell must contain {{0}}",
+                    template
+                )))
+            }
+            None => return Ok(None),
+        };
+        Ok(Some(shell))
+    }
+
+    fn template(&self) -> &str {
+        match self {
+            Self::Sh => "sh -e -c {0}",
+            Self::Bash => "bash --noprofile --norc -eo pipefail -c {0}",
+            Self::Python3 => "python3 -c {0}",
+            Self::Powershell => "powershell.exe -NoProfile -NonInteractive -Command {0}",
+            Self::Custom(template) => template,
+        }
+    }
+
+    pub fn command(&self, input: &str) -> Vec<String> {
+        let template = self.template();
+        shell_words::split(template)
+            .unwrap_or_else(|_| template.split_whitespace().map(|t| t.to_string()).collect())
+            .iter()
+            .map(|token| token.replace("{0}", input))
+            .collect()
+    }
+}
+
 pub struct Variable {
     pub name: String,
     pub default_value: Option<String>,
@@ -34,9 +109,38 @@ impl Variable {
     }
 }
 
+pub struct Checkout {
+    pub repo: String,
+    pub reference: Option<String>,
+    pub depth: Option<i64>,
+    pub submodules: bool,
+    pub path: Option<String>,
+}
+
+impl Checkout {
+    pub fn load(yaml: &Yaml) -> Result<Option<Self>> {
+        if yaml.is_badvalue() {
+            return Ok(None);
+        }
+        let repo = yaml["repo"]
+            .as_str()
+            .ok_or_else(|| BldError::YamlError("checkout must define a repo".to_string()))?
+            .to_string();
+        Ok(Some(Self {
+            repo,
+            reference: yaml["ref"].as_str().map(|r| r.to_string()),
+            depth: yaml["depth"].as_i64(),
+            submodules: yaml["submodules"].as_bool().or(Some(false)).unwrap(),
+            path: yaml["path"].as_str().map(|p| p.to_string()),
+        }))
+    }
+}
+
 pub struct BuildStep {
     pub name: Option<String>,
     pub working_dir: Option<String>,
+    pub shell: Option<Shell>,
+    pub checkout: Option<Checkout>,
     pub call: Option<String>,
     pub commands: Vec<String>,
 }
@@ -45,12 +149,16 @@ impl BuildStep {
     pub fn new(
         name: Option<String>,
         working_dir: Option<String>,
+        shell: Option<Shell>,
+        checkout: Option<Checkout>,
         call: Option<String>,
         commands: Vec<String>,
     ) -> Self {
         Self {
             name,
             working_dir,
+            shell,
+            checkout,
             call,
             commands,
         }
@@ -83,13 +191,59 @@ impl Artifacts {
     }
 }
 
+pub enum TriggerOn {
+    Success,
+    Failure,
+    Always,
+}
+
+pub struct Trigger {
+    pub pipeline: String,
+    pub on: TriggerOn,
+    pub variables: HashMap<String, String>,
+}
+
+impl Trigger {
+    pub fn load(yaml: &Yaml) -> Result<Self> {
+        let pipeline = yaml["pipeline"]
+            .as_str()
+            .ok_or("trigger must define a pipeline")?
+            .to_string();
+        let on = match yaml["on"].as_str() {
+            Some("success") | None => TriggerOn::Success,
+            Some("failure") => TriggerOn::Failure,
+            Some("always") => TriggerOn::Always,
+            Some(on) => {
+                let message = format!("unknown trigger condition {}", on);
+                return Err(BldError::YamlError(message));
+            }
+        };
+        Ok(Self {
+            pipeline,
+            on,
+            variables: yaml_string_map(&yaml["with"]),
+        })
+    }
+
+    pub fn matches(&self, state: RunState) -> bool {
+        match self.on {
+            TriggerOn::Success => state == RunState::Finished,
+            TriggerOn::Failure => state == RunState::Failed,
+            TriggerOn::Always => state == RunState::Finished || state == RunState::Failed,
+        }
+    }
+}
+
 pub struct Pipeline {
     pub name: Option<String>,
     pub runs_on: RunsOn,
+    pub labels: Vec<String>,
+    pub pull: PullPolicy,
     pub dispose: bool,
     pub variables: Vec<Variable>,
     pub artifacts: Vec<Artifacts>,
     pub steps: Vec<BuildStep>,
+    pub triggers: Vec<Trigger>,
 }
 
 impl Pipeline {
@@ -101,11 +255,95 @@ impl Pipeline {
         ])
     }
 
+    pub fn validate_name(name: &str) -> Result<()> {
+        if name.is_empty() || name.contains('/') || name.contains('\\') || name.contains("..") {
+            return Err(BldError::IoError(format!("invalid pipeline name {}", name)));
+        }
+        Ok(())
+    }
+
+    pub fn check_triggers(&self, name: &str) -> Result<()> {
+        if self.triggers.iter().any(|t| t.pipeline == name) {
+            let message = format!("pipeline {} triggers itself", name);
+            return Err(BldError::YamlError(message));
+        }
+        Ok(())
+    }
+
     pub fn read(pipeline: &str) -> Result<String> {
         let path = Pipeline::get_path(pipeline)?;
         Ok(std::fs::read_to_string(path)?)
     }
 
+    fn collect(name: &str, files: &mut HashMap<String, String>) -> Result<()> {
+        if files.contains_key(name) {
+            return Ok(());
+        }
+        if !Pipeline::get_path(name)?.is_file() {
+            let message = format!("pipeline file {} not found", name);
+            return Err(BldError::IoError(message));
+        }
+        Pipeline::collect_src(name, Pipeline::read(name)?, files)
+    }
+
+    fn collect_src(name: &str, src: String, files: &mut HashMap<String, String>) -> Result<()> {
+        let pipeline = Pipeline::parse(&src)?;
+        pipeline.check_triggers(name)?;
+        files.insert(name.to_string(), src);
+        for call in pipeline.steps.iter().filter_map(|s| s.call.as_ref()) {
+            Pipeline::collect(call, files)?;
+        }
+        Ok(())
+    }
+
+    pub fn snapshot(name: &str) -> Result<HashMap<String, String>> {
+        let mut files = HashMap::new();
+        Pipeline::collect(name, &mut files)?;
+        Ok(files)
+    }
+
+    pub fn snapshot_file(file: &str) -> Result<(String, HashMap<String, String>)> {
+        let name = Path::new(file)
+            .file_stem()
+            .map(|n| n.to_string_lossy().to_string())
+            .ok_or_else(|| BldError::IoError(format!("invalid pipeline file {}", file)))?;
+        let mut files = HashMap::new();
+        Pipeline::collect_src(&name, std::fs::read_to_string(file)?, &mut files)?;
+        Ok((name, files))
+    }
+
+    pub fn required_labels(&self) -> Vec<String> {
+        let platform = match self.runs_on {
+            RunsOn::Machine => "machine",
+            RunsOn::Docker(_) => "docker",
+        };
+        let mut labels = vec![platform.to_string()];
+        labels.extend(self.labels.iter().cloned());
+        labels
+    }
+
+    pub fn apply_variables<'a, I>(&self, txt: &str, vars: I) -> String
+    where
+        I: Iterator<Item = (&'a String, &'a String)>,
+    {
+        let mut txt_with_vars = String::from(txt);
+        for (key, value) in vars {
+            let full_name = format!("{}{}", VAR_TOKEN, &key);
+            txt_with_vars = txt_with_vars.replace(&full_name, &value);
+        }
+        for variable in self.variables.iter() {
+            let full_name = format!("{}{}", VAR_TOKEN, &variable.name);
+            let value = variable
+                .default_value
+                .as_ref()
+                .map(|d| d.to_string())
+                .or_else(|| Some(String::new()))
+                .unwrap();
+            txt_with_vars = txt_with_vars.replace(&full_name, &value);
+        }
+        txt_with_vars
+    }
+
     pub fn parse(src: &str) -> Result<Pipeline> {
         let yaml = YamlLoader::load_from_str(&src)?;
         if yaml.is_empty() {
@@ -123,10 +361,25 @@ impl Pipeline {
                 Some("machine") | None => RunsOn::Machine,
                 Some(target) => RunsOn::Docker(target.to_string()),
             },
+            labels: yaml["labels"]
+                .as_vec()
+                .or(Some(&EMPTY_YAML_VEC))
+                .unwrap()
+                .iter()
+                .filter_map(|l| l.as_str().map(|l| l.to_string()))
+                .collect(),
+            pull: PullPolicy::load(yaml)?,
             dispose: yaml["dispose"].as_bool().or(Some(true)).unwrap(),
             variables: Self::variables(yaml)?,
             artifacts: Self::artifacts(yaml),
-            steps: Self::steps(yaml),
+            steps: Self::steps(yaml)?,
+            triggers: yaml["triggers"]
+                .as_vec()
+                .or(Some(&EMPTY_YAML_VEC))
+                .unwrap()
+                .iter()
+                .map(|t| Trigger::load(t))
+                .collect::<Result<Vec<Trigger>>>()?,
         })
     }
 
@@ -160,9 +413,10 @@ impl Pipeline {
         artifacts
     }
 
-    fn steps(yaml: &Yaml) -> Vec<BuildStep> {
+    fn steps(yaml: &Yaml) -> Result<Vec<BuildStep>> {
         let mut steps = Vec::<BuildStep>::new();
         let working_dir = yaml["working-dir"].as_str().map(|w| w.to_string());
+        let shell = Shell::load(yaml)?;
         if let Some(entries) = &yaml["steps"].as_vec() {
             for step in entries.iter() {
                 let name = step["name"].as_str().map(|n| n.to_string());
@@ -170,6 +424,8 @@ impl Pipeline {
                     .as_str()
                     .map(|w| w.to_string())
                     .or_else(|| working_dir.clone());
+                let shell = Shell::load(step)?.or_else(|| shell.clone());
+                let checkout = Checkout::load(&step["checkout"])?;
                 let call = step["call"].as_str().map(|p| p.to_string());
                 let commands: Vec<String> = step["exec"]
                     .as_vec()
@@ -179,9 +435,67 @@ impl Pipeline {
                     .map(|c| c["sh"].as_str().or(Some("")).unwrap().to_string())
                     .filter(|c| !c.is_empty())
                     .collect();
-                steps.push(BuildStep::new(name, working_dir, call, commands));
+                steps.push(BuildStep::new(
+                    name,
+                    working_dir,
+                    shell,
+                    checkout,
+                    call,
+                    commands,
+                ));
             }
         }
-        steps
+        Ok(steps)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use yaml_rust::YamlLoader;
+
+    fn shell(value: &str) -> Result<Option<Shell>> {
+        let yaml = YamlLoader::load_from_str(&format!("shell: {}", value)).unwrap();
+        Shell::load(&yaml[0])
+    }
+
+    #[test]
+    fn shell_command_passes_the_input_as_one_argument() {
+        assert_eq!(
+            Shell::Sh.command("echo 'a b' && ls"),
+            vec!["sh", "-e", "-c", "echo 'a b' && ls"]
+        );
+        assert_eq!(
+            Shell::Bash.command("echo $HOME"),
+            vec![
+                "bash",
+                "--noprofile",
+                "--norc",
+                "-eo",
+                "pipefail",
+                "-c",
+                "echo $HOME"
+            ]
+        );
+    }
+
+    #[test]
+    fn shell_command_splits_custom_templates_with_quotes() {
+        let custom = Shell::Custom("docker exec 'my container' sh -c {0}".to_string());
+        assert_eq!(
+            custom.command("make test"),
+            vec!["docker", "exec", "my container", "sh", "-c", "make test"]
+        );
+    }
+
+    #[test]
+    fn shell_load_validates_custom_templates() {
+        assert!(matches!(shell("bash"), Ok(Some(Shell::Bash))));
+        assert!(matches!(
+            shell("\"node -e {0}\""),
+            Ok(Some(Shell::Custom(_)))
+        ));
+        assert!(shell("node").is_err());
+        assert!(shell("\"node -e '{0}\"").is_err());
     }
 }
@@ -0,0 +1,145 @@
+use crate::config::definitions::{GET, PUSH};
+use crate::run::{BuildStep, Machine, Pipeline, RunsOn, Shell};
+use crate::types::{BldError, Result};
+use std::collections::HashMap;
+
+pub struct Plan<'a> {
+    files: Option<&'a HashMap<String, String>>,
+    lines: Vec<String>,
+    stack: Vec<String>,
+}
+
+impl<'a> Plan<'a> {
+    pub fn from_file(
+        name: &str,
+        vars: &HashMap<String, String>,
+        files: Option<&'a HashMap<String, String>>,
+    ) -> Result<String> {
+        let mut plan = Self {
+            files,
+            lines: Vec::new(),
+            stack: Vec::new(),
+        };
+        plan.pipeline(name, vars, 0)?;
+        Ok(plan.lines.join("\n"))
+    }
+
+    fn read(&self, name: &str) -> Result<String> {
+        match self.files.and_then(|f| f.get(name)) {
+            Some(src) => Ok(src.to_string()),
+            None => Pipeline::read(name),
+        }
+    }
+
+    fn line(&mut self, depth: usize, text: &str) {
+        self.lines.push(format!("{}{}", "  ".repeat(depth), text));
+    }
+
+    fn shell(shell: &Option<Shell>, runs_on: &RunsOn) -> String {
+        let shell = match (shell, runs_on) {
+            (Some(shell), _) => shell.clone(),
+            (None, RunsOn::Machine) => Machine::default_shell(),
+            (None, RunsOn::Docker(_)) => Shell::Sh,
+        };
+        match shell {
+            Shell::Sh => "sh".to_string(),
+            Shell::Bash => "bash".to_string(),
+            Shell::Python3 => "python3".to_string(),
+            Shell::Powershell => "powershell".to_string(),
+            Shell::Custom(template) => template,
+        }
+    }
+
+    fn pipeline(&mut self, name: &str, vars: &HashMap<String, String>, depth: usize) -> Result<()> {
+        if self.stack.iter().any(|n| n == name) {
+            let message = format!("pipeline {} calls itself", name);
+            return Err(BldError::Other(message));
+        }
+        let pip = Pipeline::parse(&self.read(name)?)?;
+        self.stack.push(name.to_string());
+
+        self.line(depth, &format!("Pipeline: {}", name));
+        if let Some(title) = &pip.name {
+            self.line(depth + 1, &format!("Name: {}", title));
+        }
+        self.line(depth + 1, &format!("Runs on: {}", pip.runs_on));
+        for variable in pip.variables.iter() {
+            let value = vars
+                .get(&variable.name)
+                .or_else(|| variable.default_value.as_ref())
+                .map(|v| v.to_string())
+                .unwrap_or_default();
+            self.line(
+                depth + 1,
+                &format!("Variable: {} = {}", variable.name, value),
+            );
+        }
+        self.artifacts(&pip, vars, &None, depth + 1);
+        for step in pip.steps.iter() {
+            self.step(&pip, step, vars, depth + 1)?;
+            self.artifacts(&pip, vars, &step.name, depth + 1);
+        }
+        for trigger in pip.triggers.iter() {
+            self.line(depth + 1, &format!("Trigger: {}", trigger.pipeline));
+        }
+
+        self.stack.pop();
+        Ok(())
+    }
+
+    fn step(
+        &mut self,
+        pip: &Pipeline,
+        step: &BuildStep,
+        vars: &HashMap<String, String>,
+        depth: usize,
+    ) -> Result<()> {
+        let name = step.name.as_deref().unwrap_or("unnamed");
+        self.line(depth, &format!("Step: {}", name));
+        if let Some(working_dir) = &step.working_dir {
+            self.line(depth + 1, &format!("Working dir: {}", working_dir));
+        }
+        if let Some(checkout) = &step.checkout {
+            let reference = checkout
+                .reference
+                .as_ref()
+                .map(|r| pip.apply_variables(r, vars.iter()))
+                .unwrap_or_else(|| "HEAD".to_string());
+            let repo = pip.apply_variables(&checkout.repo, vars.iter());
+            self.line(depth + 1, &format!("Checkout: {} at {}", repo, reference));
+        }
+        if let Some(call) = &step.call {
+            self.line(depth + 1, &format!("Call: {}", call));
+            self.pipeline(call, vars, depth + 2)?;
+        }
+        for command in step.commands.iter() {
+            let command = pip.apply_variables(command, vars.iter());
+            let shell = Plan::shell(&step.shell, &pip.runs_on);
+            self.line(depth + 1, &format!("Command ({}): {}", shell, command));
+        }
+        Ok(())
+    }
+
+    fn artifacts(
+        &mut self,
+        pip: &Pipeline,
+        vars: &HashMap<String, String>,
+        after: &Option<String>,
+        depth: usize,
+    ) {
+        for artifact in pip.artifacts.iter().filter(|a| &a.after == after) {
+            let (method, from, to) = match (&artifact.method, &artifact.from, &artifact.to) {
+                (Some(method), Some(from), Some(to)) if method == PUSH || method == GET => {
+                    (method, from, to)
+                }
+                _ => continue,
+            };
+            let from = pip.apply_variables(from, vars.iter());
+            let to = pip.apply_variables(to, vars.iter());
+            self.line(
+                depth,
+                &format!("Artifacts ({}): from {} to {}", method, from, to),
+            );
+        }
+    }
+}
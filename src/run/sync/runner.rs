@@ -1,20 +1,22 @@
-use crate::config::definitions::{GET, PUSH, VAR_TOKEN};
+use crate::config::definitions::{GET, PUSH, VAR_CHECKOUT_SHA};
 use crate::config::BldConfig;
 use crate::persist::{Execution, Logger, NullExec};
-use crate::run::{BuildStep, Container, Machine, Pipeline, RunsOn};
-use crate::types::{CheckStopSignal, Result};
+use crate::run::{BuildStep, Checkout, Container, Machine, Pipeline, RunsOn, Shell};
+use crate::types::{BldError, CheckStopSignal, Result, RunState, RunSummary, StopReceiver};
 use std::collections::HashMap;
 use std::future::Future;
+use std::path::Path;
 use std::pin::Pin;
-use std::rc::Rc;
-use std::sync::mpsc::Receiver;
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::process::Command;
+use uuid::Uuid;
 
-type RecursiveFuture = Pin<Box<dyn Future<Output = Result<()>>>>;
-type AtomicExec = Arc<Mutex<dyn Execution>>;
-type AtomicLog = Arc<Mutex<dyn Logger>>;
-type AtomicRecv = Arc<Mutex<Receiver<bool>>>;
+type RecursiveFuture = Pin<Box<dyn Future<Output = Result<()>> + Send>>;
+type AtomicExec = Arc<Mutex<dyn Execution + Send>>;
+type AtomicLog = Arc<Mutex<dyn Logger + Send>>;
 type AtomicVars = Arc<HashMap<String, String>>;
+type AtomicFiles = Arc<HashMap<String, String>>;
 
 pub enum TargetPlatform {
     Machine(Box<Machine>),
@@ -25,24 +27,35 @@ pub struct Runner {
     pub ex: AtomicExec,
     pub lg: AtomicLog,
     pub pip: Pipeline,
-    pub cm: Option<AtomicRecv>,
+    pub cm: Option<StopReceiver>,
     pub vars: AtomicVars,
+    pub files: Option<AtomicFiles>,
+    pub builtins: HashMap<String, String>,
     pub platform: TargetPlatform,
+    pub summary: RunSummary,
+}
+
+fn quote(text: &str) -> String {
+    format!("'{}'", text.replace('\'', "'\\''"))
 }
 
 impl Runner {
     async fn new(
-        cfg: Rc<BldConfig>,
+        cfg: Arc<BldConfig>,
         ex: AtomicExec,
         lg: AtomicLog,
         pip: Pipeline,
-        cm: Option<AtomicRecv>,
+        cm: Option<StopReceiver>,
         vars: AtomicVars,
+        files: Option<AtomicFiles>,
     ) -> Result<Runner> {
+        let run = ex.lock().unwrap().id();
         let platform = match &pip.runs_on {
-            RunsOn::Machine => TargetPlatform::Machine(Box::new(Machine::new(lg.clone())?)),
+            RunsOn::Machine => {
+                TargetPlatform::Machine(Box::new(Machine::new(&cfg, run.as_deref(), lg.clone())?))
+            }
             RunsOn::Docker(img) => TargetPlatform::Container(Box::new(
-                Container::new(img, cfg.clone(), lg.clone()).await?,
+                Container::new(img, &pip.pull, cfg.clone(), lg.clone()).await?,
             )),
         };
         Ok(Runner {
@@ -51,7 +64,10 @@ impl Runner {
             pip,
             cm,
             vars,
+            files,
+            builtins: HashMap::new(),
             platform,
+            summary: RunSummary::default(),
         })
     }
 
@@ -60,14 +76,52 @@ impl Runner {
         lg.dumpln(message);
     }
 
+    fn persist_error(&self, result: Result<()>) {
+        if let Err(e) = result {
+            let mut lg = self.lg.lock().unwrap();
+            lg.error(&format!("Couldn't persist the run: {}", e.to_string()));
+        }
+    }
+
     fn persist_start(&mut self) {
-        let mut exec = self.ex.lock().unwrap();
-        let _ = exec.update(true);
+        let result = self.ex.lock().unwrap().update(RunState::Running);
+        self.persist_error(result);
+    }
+
+    fn persist_end(&mut self, state: RunState) {
+        let result = self.ex.lock().unwrap().update(state);
+        self.persist_error(result);
+    }
+
+    fn persist_summary(&mut self) {
+        if self.summary.is_empty() {
+            return;
+        }
+        {
+            let mut logger = self.lg.lock().unwrap();
+            logger.info("Run summary:");
+            logger.dumpln(&self.summary.table());
+        }
+        let result = self.ex.lock().unwrap().summary(&self.summary);
+        self.persist_error(result);
     }
 
-    fn persist_end(&mut self) {
-        let mut exec = self.ex.lock().unwrap();
-        let _ = exec.update(false);
+    fn triggers(&self, state: RunState) {
+        for trigger in self.pip.triggers.iter().filter(|t| t.matches(state)) {
+            let variables = trigger
+                .variables
+                .iter()
+                .map(|(k, v)| (k.to_string(), self.apply_variables(v)))
+                .collect();
+            self.dumpln(&format!("Triggering pipeline: {}", trigger.pipeline));
+            let result = {
+                let mut exec = self.ex.lock().unwrap();
+                exec.trigger(&trigger.pipeline, variables)
+            };
+            if let Err(e) = result {
+                self.dumpln(&e.to_string());
+            }
+        }
     }
 
     fn info(&self) {
@@ -79,22 +133,8 @@ impl Runner {
     }
 
     fn apply_variables(&self, txt: &str) -> String {
-        let mut txt_with_vars = String::from(txt);
-        for (key, value) in self.vars.iter() {
-            let full_name = format!("{}{}", VAR_TOKEN, &key);
-            txt_with_vars = txt_with_vars.replace(&full_name, &value);
-        }
-        for variable in self.pip.variables.iter() {
-            let full_name = format!("{}{}", VAR_TOKEN, &variable.name);
-            let value = variable
-                .default_value
-                .as_ref()
-                .map(|d| d.to_string())
-                .or_else(|| Some(String::new()))
-                .unwrap();
-            txt_with_vars = txt_with_vars.replace(&full_name, &value);
-        }
-        txt_with_vars
+        self.pip
+            .apply_variables(txt, self.builtins.iter().chain(self.vars.iter()))
     }
 
     async fn artifacts(&self, name: &Option<String>) -> Result<()> {
@@ -141,48 +181,219 @@ impl Runner {
         Ok(())
     }
 
-    async fn steps(&mut self) -> Result<()> {
-        for step in self.pip.steps.iter() {
-            self.step(&step).await?;
-            self.artifacts(&step.name).await?;
+    async fn sh(
+        &self,
+        working_dir: &Option<String>,
+        shell: &Option<Shell>,
+        input: &str,
+    ) -> Result<()> {
+        match &self.platform {
+            TargetPlatform::Container(container) => {
+                container.sh(working_dir, shell, input, &self.cm).await
+            }
+            TargetPlatform::Machine(machine) => {
+                machine.sh(working_dir, shell, input, &self.cm).await
+            }
+        }
+    }
+
+    async fn capture(
+        &self,
+        working_dir: &Option<String>,
+        shell: &Option<Shell>,
+        input: &str,
+    ) -> Result<String> {
+        match &self.platform {
+            TargetPlatform::Container(container) => {
+                container.capture(working_dir, shell, input).await
+            }
+            TargetPlatform::Machine(machine) => machine.capture(working_dir, shell, input).await,
+        }
+    }
+
+    fn repository_name(repo: &str) -> String {
+        let name = repo
+            .trim_end_matches('/')
+            .rsplit(|c| c == '/' || c == ':')
+            .next()
+            .unwrap_or(repo);
+        name.trim_end_matches(".git").to_string()
+    }
+
+    async fn bundle(repo: &str) -> Result<String> {
+        let bundle = std::env::temp_dir()
+            .join(format!("bld-{}.bundle", Uuid::new_v4()))
+            .display()
+            .to_string();
+        let output = Command::new("git")
+            .args(&["-C", repo, "bundle", "create", &bundle, "--all"])
+            .output()
+            .await?;
+        if !output.status.success() {
+            let message = String::from_utf8_lossy(&output.stderr).trim().to_string();
+            return Err(BldError::Other(message));
+        }
+        Ok(bundle)
+    }
+
+    async fn checkout(&self, working_dir: &Option<String>, checkout: &Checkout) -> Result<String> {
+        let repo = self.apply_variables(&checkout.repo);
+        let reference = checkout
+            .reference
+            .as_ref()
+            .map(|r| self.apply_variables(r))
+            .unwrap_or_else(|| "HEAD".to_string());
+        let path = checkout
+            .path
+            .as_ref()
+            .map(|p| self.apply_variables(p))
+            .unwrap_or_else(|| Runner::repository_name(&repo));
+        {
+            let mut logger = self.lg.lock().unwrap();
+            logger.dumpln(&format!("Checkout: {} at {}", repo, reference));
+        }
+        let is_local = Path::new(&repo).is_dir();
+        let (source, depth) = match &self.platform {
+            TargetPlatform::Container(container) if is_local => {
+                let bundle = Runner::bundle(&repo).await?;
+                let target = format!("/tmp/{}", Runner::repository_name(&bundle));
+                let result = container.copy_into(&bundle, &target).await;
+                std::fs::remove_file(&bundle)?;
+                result?;
+                (target, None)
+            }
+            TargetPlatform::Machine(_) if is_local => {
+                let source = std::fs::canonicalize(&repo)?.display().to_string();
+                (format!("file://{}", source), checkout.depth)
+            }
+            _ => (repo.clone(), checkout.depth),
+        };
+        let depth = depth
+            .map(|d| format!(" --depth {}", d))
+            .unwrap_or_else(String::new);
+        let shell = &Some(Shell::Sh);
+        let git = format!("git -C {}", quote(&path));
+        self.sh(working_dir, shell, &format!("git init -q {}", quote(&path)))
+            .await?;
+        let remotes = self
+            .capture(working_dir, shell, &format!("{} remote", git))
+            .await?;
+        let remote = match remotes.lines().any(|r| r.trim() == "origin") {
+            true => "set-url",
+            false => "add",
+        };
+        let mut commands = vec![
+            format!("{} remote {} origin {}", git, remote, quote(&source)),
+            format!("{} fetch{} origin {}", git, depth, quote(&reference)),
+            format!("{} checkout -q --force --detach FETCH_HEAD", git),
+        ];
+        if checkout.submodules {
+            commands.push(format!(
+                "{} submodule update --init --recursive{}",
+                git, depth
+            ));
+        }
+        for command in commands.iter() {
+            self.sh(working_dir, shell, command).await?;
             self.cm.check_stop_signal()?;
         }
+        let commit = self
+            .capture(working_dir, shell, &format!("{} rev-parse HEAD", git))
+            .await?;
+        if commit.len() != 40 || !commit.chars().all(|c| c.is_ascii_hexdigit()) {
+            let message = format!("could not resolve commit for repository {}", repo);
+            return Err(BldError::Other(message));
+        }
+        {
+            let mut logger = self.lg.lock().unwrap();
+            logger.dumpln(&format!("Checked out commit: {}", commit));
+        }
+        {
+            let mut exec = self.ex.lock().unwrap();
+            exec.checkout(&repo, &reference, &commit)?;
+        }
+        Ok(commit)
+    }
+
+    fn pull_time(&self) -> Duration {
+        match &self.platform {
+            TargetPlatform::Container(container) => container.pull_time,
+            TargetPlatform::Machine(_) => Duration::default(),
+        }
+    }
+
+    async fn steps(&mut self) -> Result<()> {
+        for (index, step) in self.pip.steps.iter().enumerate() {
+            {
+                let mut logger = self.lg.lock().unwrap();
+                logger.step(step.name.as_deref());
+                if let Some(name) = &step.name {
+                    logger.info(&format!("Step: {}", name));
+                }
+            }
+            let started = Instant::now();
+            let mut result = Ok(());
+            if let Some(checkout) = &step.checkout {
+                match self.checkout(&step.working_dir, checkout).await {
+                    Ok(commit) => {
+                        self.builtins.insert(VAR_CHECKOUT_SHA.to_string(), commit);
+                    }
+                    Err(e) => result = Err(e),
+                }
+            }
+            if result.is_ok() {
+                result = self.step(&step).await;
+            }
+            let artifacts_started = Instant::now();
+            if result.is_ok() {
+                result = self.artifacts(&step.name).await;
+            }
+            let artifacts = artifacts_started.elapsed();
+            if result.is_ok() {
+                result = self.cm.check_stop_signal();
+            }
+            let name = step
+                .name
+                .clone()
+                .unwrap_or_else(|| format!("step {}", index + 1));
+            self.summary.push(
+                &name,
+                &result,
+                started.elapsed(),
+                Duration::default(),
+                artifacts,
+            );
+            result?;
+        }
+        self.lg.lock().unwrap().step(None);
         Ok(())
     }
 
     async fn step(&self, step: &BuildStep) -> Result<()> {
-        if let Some(name) = &step.name {
-            let mut logger = self.lg.lock().unwrap();
-            logger.info(&format!("Step: {}", name));
-        }
         let comm = match &self.cm {
             Some(comm) => Some(comm.clone()),
             None => None,
         };
         if let Some(call) = &step.call {
-            Runner::from_file(
+            let mut vars = (*self.vars).clone();
+            vars.extend(self.builtins.clone());
+            Runner::load(
                 call.clone(),
                 NullExec::atom(),
                 self.lg.clone(),
                 comm,
-                self.vars.clone(),
+                Arc::new(vars),
+                self.files.clone(),
+                true,
             )
-            .await
             .await?;
+            self.lg.lock().unwrap().step(step.name.as_deref());
         }
         self.cm.check_stop_signal()?;
         for command in step.commands.iter() {
             let command_with_vars = self.apply_variables(&command);
-            match &self.platform {
-                TargetPlatform::Container(container) => {
-                    container
-                        .sh(&step.working_dir, &command_with_vars, &self.cm)
-                        .await?
-                }
-                TargetPlatform::Machine(machine) => {
-                    machine.sh(&step.working_dir, &command_with_vars)?
-                }
-            }
+            self.sh(&step.working_dir, &step.shell, &command_with_vars)
+                .await?;
             self.cm.check_stop_signal()?;
         }
         Ok(())
@@ -198,43 +409,86 @@ impl Runner {
         Ok(())
     }
 
-    pub async fn from_src(
+    fn run(
+        name: String,
         src: String,
         ex: AtomicExec,
         lg: AtomicLog,
-        cm: Option<AtomicRecv>,
+        cm: Option<StopReceiver>,
         vars: AtomicVars,
+        files: Option<AtomicFiles>,
+        nested: bool,
     ) -> RecursiveFuture {
         Box::pin(async move {
-            let config = Rc::new(BldConfig::load()?);
+            let config = Arc::new(BldConfig::load()?);
             let pip = Pipeline::parse(&src)?;
-            let mut runner = Runner::new(Rc::clone(&config), ex, lg, pip, cm, vars).await?;
+            pip.check_triggers(&name)?;
+            let started = Instant::now();
+            let mut runner = Runner::new(config, ex, lg, pip, cm, vars, files).await?;
 
             runner.persist_start();
             runner.info();
-            match runner.artifacts(&None).await {
-                Ok(_) => {
-                    if let Err(e) = runner.steps().await {
-                        runner.dumpln(&e.to_string());
-                    }
-                }
-                Err(e) => runner.dumpln(&e.to_string()),
+            let artifacts_started = Instant::now();
+            let result = runner.artifacts(&None).await;
+            let pull_time = runner.pull_time();
+            runner.summary.push(
+                "setup",
+                &result,
+                started.elapsed(),
+                pull_time,
+                artifacts_started.elapsed(),
+            );
+            let result = match result {
+                Ok(_) => runner.steps().await,
+                Err(e) => Err(e),
+            };
+            let state = match &result {
+                Ok(_) => RunState::Finished,
+                Err(BldError::Stopped) => RunState::Stopped,
+                Err(_) => RunState::Failed,
+            };
+            if let Err(e) = &result {
+                runner.dumpln(&e.to_string());
+            }
+            if !nested {
+                runner.persist_summary();
+            }
+            runner.persist_end(state);
+            runner.triggers(state);
+            runner.dispose().await?;
+            match result {
+                Err(BldError::Stopped) => Err(BldError::Stopped),
+                _ => Ok(()),
             }
-            runner.persist_end();
-            runner.dispose().await
         })
     }
 
-    pub async fn from_file(
+    pub fn from_file(
+        name: String,
+        ex: AtomicExec,
+        lg: AtomicLog,
+        cm: Option<StopReceiver>,
+        vars: AtomicVars,
+        files: Option<AtomicFiles>,
+    ) -> RecursiveFuture {
+        Runner::load(name, ex, lg, cm, vars, files, false)
+    }
+
+    fn load(
         name: String,
         ex: AtomicExec,
         lg: AtomicLog,
-        cm: Option<AtomicRecv>,
+        cm: Option<StopReceiver>,
         vars: AtomicVars,
+        files: Option<AtomicFiles>,
+        nested: bool,
     ) -> RecursiveFuture {
         Box::pin(async move {
-            let src = Pipeline::read(&name)?;
-            Runner::from_src(src, ex, lg, cm, vars).await.await
+            let src = match files.as_ref().and_then(|f| f.get(&name)) {
+                Some(src) => src.to_string(),
+                None => Pipeline::read(&name)?,
+            };
+            Runner::run(name, src, ex, lg, cm, vars, files, nested).await
         })
     }
 }
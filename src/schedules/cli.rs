@@ -0,0 +1,14 @@
+use crate::config::definitions::VERSION;
+use clap::{App, Arg, SubCommand};
+
+pub fn command() -> App<'static, 'static> {
+    let server = Arg::with_name("server")
+        .short("s")
+        .long("server")
+        .takes_value(true)
+        .help("The name of the server from which to fetch the pipeline schedules");
+    SubCommand::with_name("schedules")
+        .about("Lists the scheduled pipelines of a server and their next run")
+        .version(VERSION)
+        .args(&[server])
+}
@@ -0,0 +1,5 @@
+mod cli;
+mod exec;
+
+pub use cli::*;
+pub use exec::*;
@@ -0,0 +1,32 @@
+use crate::config::definitions::VERSION;
+use clap::{App, Arg, SubCommand};
+
+pub fn command() -> App<'static, 'static> {
+    let pattern = Arg::with_name("pattern")
+        .help("The regular expression to search for in the run logs")
+        .takes_value(true)
+        .required(true);
+    let pipeline = Arg::with_name("pipeline")
+        .short("p")
+        .long("pipeline")
+        .help("Search only the runs of the given pipeline")
+        .takes_value(true);
+    let since = Arg::with_name("since")
+        .long("since")
+        .help("Search only the runs started within the given duration e.g. 12h, 7d")
+        .takes_value(true);
+    let context = Arg::with_name("context")
+        .short("C")
+        .long("context")
+        .help("The number of lines to show before and after each match")
+        .takes_value(true);
+    let server = Arg::with_name("server")
+        .short("s")
+        .long("server")
+        .help("The name of the server to search")
+        .takes_value(true);
+    SubCommand::with_name("search")
+        .about("Searches the logs of the runs on a bld server")
+        .version(VERSION)
+        .args(&[pattern, pipeline, since, context, server])
+}
@@ -0,0 +1,158 @@
+use crate::config::definitions::SEARCH_MAX_RESULTS;
+use crate::config::BldConfig;
+use crate::helpers::errors::auth_for_server_invalid;
+use crate::helpers::request::{fetch_post, headers};
+use crate::helpers::term::print_info;
+use crate::path;
+use crate::persist::{Database, FileScanner, LogIndex, PipelineModel, Trigram};
+use crate::types::{parse_duration, LogEntry, Result, SearchInfo, SearchMatch, SearchResults};
+use actix::System;
+use chrono::{Duration, Utc};
+use clap::ArgMatches;
+use regex::Regex;
+use std::collections::VecDeque;
+use std::io::{BufRead, BufReader};
+use std::path::PathBuf;
+
+fn may_match(logs: &str, trigrams: &[Trigram]) -> bool {
+    match LogIndex::load(logs) {
+        Ok(Some(index)) => trigrams.iter().all(|t| index.contains(t)),
+        _ => true,
+    }
+}
+
+fn search_log(
+    logs: &str,
+    pipeline: &PipelineModel,
+    regex: &Regex,
+    context: usize,
+    matches: &mut Vec<SearchMatch>,
+) -> Result<()> {
+    let mut reader = BufReader::new(FileScanner::new(logs)?.into_reader());
+    let mut before = VecDeque::with_capacity(context);
+    let mut pending = Vec::<usize>::new();
+    let mut buffer = Vec::new();
+    let mut line = 0;
+    loop {
+        buffer.clear();
+        if reader.read_until(b'\n', &mut buffer)? == 0 {
+            break;
+        }
+        line += 1;
+        let text = String::from_utf8_lossy(&buffer);
+        let entry = LogEntry::parse(text.trim_end_matches(|c| c == '\n' || c == '\r'));
+        for index in pending.iter() {
+            matches[*index].after.push(entry.text.clone());
+        }
+        pending.retain(|index| matches[*index].after.len() < context);
+        let full = matches.len() >= SEARCH_MAX_RESULTS;
+        if full && pending.is_empty() {
+            break;
+        }
+        if !full && regex.is_match(&entry.text) {
+            matches.push(SearchMatch {
+                id: pipeline.id.to_string(),
+                pipeline: pipeline.name.to_string(),
+                line,
+                step: entry.step.clone(),
+                before: before.iter().cloned().collect(),
+                text: entry.text.clone(),
+                after: Vec::new(),
+            });
+            if context > 0 {
+                pending.push(matches.len() - 1);
+            }
+        }
+        if context > 0 {
+            if before.len() == context {
+                before.pop_front();
+            }
+            before.push_back(entry.text);
+        }
+    }
+    Ok(())
+}
+
+pub fn search_info(config: &BldConfig, info: &SearchInfo) -> Result<SearchResults> {
+    let regex = Regex::new(&info.pattern)?;
+    let trigrams = LogIndex::required(&info.pattern);
+    let since = match &info.since {
+        Some(since) => {
+            let secs = parse_duration(since)?;
+            Some((Utc::now() - Duration::seconds(secs as i64)).to_string())
+        }
+        None => None,
+    };
+    let db = Database::connect(&config.local.db)?;
+    let mut matches = Vec::new();
+    for pipeline in db.all()?.iter() {
+        if let Some(name) = &info.pipeline {
+            if &pipeline.name != name {
+                continue;
+            }
+        }
+        if let Some(since) = &since {
+            if &pipeline.start_date_time < since {
+                continue;
+            }
+        }
+        let logs = path![
+            &config.local.logs,
+            format!("{}-{}", pipeline.name, pipeline.id)
+        ]
+        .display()
+        .to_string();
+        if !may_match(&logs, &trigrams) {
+            continue;
+        }
+        if search_log(&logs, pipeline, &regex, info.context, &mut matches).is_err() {
+            continue;
+        }
+        if matches.len() >= SEARCH_MAX_RESULTS {
+            break;
+        }
+    }
+    let truncated = matches.len() >= SEARCH_MAX_RESULTS;
+    Ok(SearchResults { matches, truncated })
+}
+pub fn exec(matches: &ArgMatches<'_>) -> Result<()> {
+    let config = BldConfig::load()?;
+    let context = match matches.value_of("context") {
+        Some(context) => context.parse::<usize>()?,
+        None => 0,
+    };
+    let since = matches.value_of("since").map(|s| s.to_string());
+    if let Some(since) = &since {
+        parse_duration(since)?;
+    }
+    let info = SearchInfo {
+        pattern: matches.value_of("pattern").unwrap_or_default().to_string(),
+        pipeline: matches.value_of("pipeline").map(|p| p.to_string()),
+        since,
+        context,
+    };
+    let srv = config.remote.server_or_first(matches.value_of("server"))?;
+    let (name, auth) = match &srv.same_auth_as {
+        Some(name) => match config.remote.servers.iter().find(|s| &s.name == name) {
+            Some(srv) => (&srv.name, &srv.auth),
+            None => return auth_for_server_invalid(),
+        },
+        None => (&srv.name, &srv.auth),
+    };
+    let urls = srv.urls("/search");
+    let headers = headers(name, auth)?;
+    let mut system = System::new("bld-search");
+    let content = system.block_on(fetch_post(urls, headers, info))?;
+    let results: SearchResults = serde_json::from_slice(&content)?;
+    for result in results.matches.iter() {
+        println!("\n{}", result.to_string());
+    }
+    if results.truncated {
+        println!();
+        print_info(&format!(
+            "Showing the first {} matches, narrow the search with --pipeline or --since",
+            results.matches.len()
+        ))?;
+    }
+    Ok(())
+}
@@ -1,7 +1,6 @@
 use crate::config::BldConfig;
-use crate::persist::Database;
+use crate::hist::{history_info, run_info};
 use crate::server::User;
-use crate::types::Result;
 use actix_web::{get, web, HttpResponse, Responder};
 
 #[get("/hist")]
@@ -16,12 +15,16 @@ pub async fn hist((user, config): (Option<User>, web::Data<BldConfig>)) -> impl
     }
 }
 
-fn history_info(config: &BldConfig) -> Result<String> {
-    let db = Database::connect(&config.local.db)?;
-    let pipelines = db.all()?;
-    let info = pipelines
-        .iter()
-        .map(|p| p.to_string())
-        .fold(String::new(), |acc, n| format!("{}\n{}\n", acc, n));
-    Ok(info)
+#[get("/hist/{id}")]
+pub async fn hist_run(
+    (user, path, config): (Option<User>, web::Path<(String,)>, web::Data<BldConfig>),
+) -> impl Responder {
+    if user.is_none() {
+        return HttpResponse::Unauthorized().body("");
+    }
+
+    match run_info(config.get_ref(), &path.into_inner().0) {
+        Ok(info) => HttpResponse::Ok().body(info),
+        Err(e) => HttpResponse::BadRequest().body(e.to_string()),
+    }
 }
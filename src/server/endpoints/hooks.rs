@@ -0,0 +1,60 @@
+use crate::config::definitions::SYSTEM_USER;
+use crate::config::{BldConfig, BldHookConfig};
+use crate::server::{Leadership, PipelineInfo, PipelinePool, PipelineQueue};
+use crate::types::{ExecInfo, Result, RunTrigger};
+use actix_web::{post, web, HttpRequest, HttpResponse};
+use serde_json::Value;
+
+#[post("/hooks/{pipeline}")]
+pub async fn hooks(
+    req: HttpRequest,
+    pipeline: web::Path<String>,
+    body: web::Bytes,
+    config: web::Data<BldConfig>,
+    pool: web::Data<PipelinePool>,
+    queue: web::Data<PipelineQueue>,
+    leadership: web::Data<Leadership>,
+) -> HttpResponse {
+    if !leadership.is_leader() {
+        return HttpResponse::ServiceUnavailable().body("server is on standby");
+    }
+    let hook = match config.local.hook(&pipeline) {
+        Some(hook) => hook,
+        None => {
+            let message = format!("no hook for pipeline {} found", pipeline);
+            return HttpResponse::NotFound().body(message);
+        }
+    };
+    let signature = req
+        .headers()
+        .get(&hook.header[..])
+        .and_then(|h| h.to_str().ok())
+        .unwrap_or("");
+    if !hook.verify(signature, &body) {
+        return HttpResponse::Unauthorized().body("");
+    }
+    match start(&config, pool, &queue, hook, &body) {
+        Ok(id) => HttpResponse::Ok().body(id),
+        Err(e) => HttpResponse::BadRequest().body(e.to_string()),
+    }
+}
+
+fn start(
+    config: &web::Data<BldConfig>,
+    pool: web::Data<PipelinePool>,
+    queue: &web::Data<PipelineQueue>,
+    hook: &BldHookConfig,
+    body: &[u8],
+) -> Result<String> {
+    let payload = serde_json::from_slice::<Value>(body)?;
+    let info = PipelineInfo::new(
+        config.clone(),
+        pool,
+        SYSTEM_USER,
+        ExecInfo::new(&hook.pipeline, Some(hook.variables(&payload)), None),
+        RunTrigger::Webhook,
+    )?;
+    let id = info.id.clone();
+    PipelineQueue::enqueue(queue, info);
+    Ok(id)
+}
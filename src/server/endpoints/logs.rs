@@ -0,0 +1,288 @@
+use crate::config::definitions::LOGS_CHUNK_SIZE;
+use crate::config::BldConfig;
+use crate::path;
+use crate::persist::Database;
+use crate::server::{PipelinePool, User};
+use crate::types::{BldError, Result};
+use actix_web::error::ErrorInternalServerError;
+use actix_web::http::header;
+use actix_web::{get, web, Error, HttpRequest, HttpResponse};
+use bytes::Bytes;
+use flate2::read::{GzDecoder, GzEncoder};
+use flate2::Compression;
+use futures::stream::{self, LocalBoxStream, StreamExt};
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use tokio::runtime::Handle;
+
+type StdResult<T, V> = std::result::Result<T, V>;
+type LogReader = Box<dyn Read + Send>;
+
+enum LogBody {
+    Full {
+        reader: LogReader,
+        gzip: bool,
+    },
+    Partial {
+        reader: LogReader,
+        start: u64,
+        end: u64,
+        len: u64,
+    },
+    Unsatisfiable {
+        len: u64,
+    },
+}
+
+#[get("/logs/{id}")]
+pub async fn logs(
+    user: Option<User>,
+    req: HttpRequest,
+    path: web::Path<(String,)>,
+    config: web::Data<BldConfig>,
+    pool: web::Data<PipelinePool>,
+) -> HttpResponse {
+    if user.is_none() {
+        return HttpResponse::Unauthorized().body("");
+    }
+
+    let id = path.into_inner().0;
+    let range = req
+        .headers()
+        .get(header::RANGE)
+        .and_then(|r| r.to_str().ok())
+        .map(|r| r.to_string());
+    let gzip = req
+        .headers()
+        .get(header::ACCEPT_ENCODING)
+        .and_then(|e| e.to_str().ok())
+        .map(|e| e.contains("gzip"))
+        .unwrap_or(false);
+    let result = pool
+        .runtime
+        .spawn_blocking(move || open_logs(config.get_ref(), &id, range.as_deref(), gzip))
+        .await;
+    let body = match result {
+        Ok(Ok(body)) => body,
+        Ok(Err(e)) => return HttpResponse::NotFound().body(e.to_string()),
+        Err(e) => return HttpResponse::InternalServerError().body(e.to_string()),
+    };
+
+    let runtime = pool.runtime.clone();
+    match body {
+        LogBody::Full { reader, gzip: true } => HttpResponse::Ok()
+            .header(header::ACCEPT_RANGES, "bytes")
+            .header(header::CONTENT_ENCODING, "gzip")
+            .streaming(chunks(runtime, reader)),
+        LogBody::Full {
+            reader,
+            gzip: false,
+        } => HttpResponse::Ok()
+            .header(header::ACCEPT_RANGES, "bytes")
+            .streaming(chunks(runtime, reader)),
+        LogBody::Partial {
+            reader,
+            start,
+            end,
+            len,
+        } => HttpResponse::PartialContent()
+            .header(header::ACCEPT_RANGES, "bytes")
+            .header(
+                header::CONTENT_RANGE,
+                format!("bytes {}-{}/{}", start, end, len),
+            )
+            .streaming(chunks(runtime, reader)),
+        LogBody::Unsatisfiable { len } => HttpResponse::RangeNotSatisfiable()
+            .header(header::CONTENT_RANGE, format!("bytes */{}", len))
+            .finish(),
+    }
+}
+
+fn open_logs(config: &BldConfig, id: &str, range: Option<&str>, gzip: bool) -> Result<LogBody> {
+    let mut db = Database::connect(&config.local.db)?;
+    db.load(id);
+    let pipeline = match &db.pipeline {
+        Some(pipeline) => pipeline,
+        None => return Err(BldError::Other("pipeline not found".to_string())),
+    };
+    let path = path![
+        &config.local.logs,
+        format!("{}-{}", pipeline.name, pipeline.id)
+    ]
+    .display()
+    .to_string();
+    let gz_path = format!("{}.gz", path);
+
+    if Path::new(&path).is_file() {
+        open_plain(&path, range, gzip)
+    } else if Path::new(&gz_path).is_file() {
+        open_gz(&gz_path, range, gzip)
+    } else {
+        Err(BldError::Other("log not found".to_string()))
+    }
+}
+
+fn open_plain(path: &str, range: Option<&str>, gzip: bool) -> Result<LogBody> {
+    let mut file = File::open(path)?;
+    let len = file.metadata()?.len();
+    if let Some(range) = range {
+        return match parse_range(range, len) {
+            Some((start, end)) => {
+                file.seek(SeekFrom::Start(start))?;
+                let reader = Box::new(file.take(end - start + 1));
+                Ok(LogBody::Partial {
+                    reader,
+                    start,
+                    end,
+                    len,
+                })
+            }
+            None => Ok(LogBody::Unsatisfiable { len }),
+        };
+    }
+    let reader: LogReader = match gzip {
+        true => Box::new(GzEncoder::new(file, Compression::default())),
+        false => Box::new(file),
+    };
+    Ok(LogBody::Full { reader, gzip })
+}
+
+fn open_gz(gz_path: &str, range: Option<&str>, gzip: bool) -> Result<LogBody> {
+    if let Some(range) = range {
+        let len = io::copy(&mut GzDecoder::new(File::open(gz_path)?), &mut io::sink())?;
+        return match parse_range(range, len) {
+            Some((start, end)) => {
+                let mut reader = GzDecoder::new(File::open(gz_path)?);
+                io::copy(&mut (&mut reader).take(start), &mut io::sink())?;
+                let reader = Box::new(reader.take(end - start + 1));
+                Ok(LogBody::Partial {
+                    reader,
+                    start,
+                    end,
+                    len,
+                })
+            }
+            None => Ok(LogBody::Unsatisfiable { len }),
+        };
+    }
+    let file = File::open(gz_path)?;
+    let reader: LogReader = match gzip {
+        true => Box::new(file),
+        false => Box::new(GzDecoder::new(file)),
+    };
+    Ok(LogBody::Full { reader, gzip })
+}
+
+fn chunks(runtime: Handle, reader: LogReader) -> LocalBoxStream<'static, StdResult<Bytes, Error>> {
+    stream::unfold(Some(reader), move |reader| {
+        let runtime = runtime.clone();
+        async move {
+            let mut reader = reader?;
+            let result = runtime
+                .spawn_blocking(move || {
+                    let mut buffer = vec![0; LOGS_CHUNK_SIZE];
+                    let read = reader.read(&mut buffer)?;
+                    buffer.truncate(read);
+                    Ok::<_, io::Error>((reader, buffer))
+                })
+                .await;
+            match result {
+                Ok(Ok((_, buffer))) if buffer.is_empty() => None,
+                Ok(Ok((reader, buffer))) => Some((Ok(Bytes::from(buffer)), Some(reader))),
+                Ok(Err(e)) => Some((Err(e.into()), None)),
+                Err(e) => Some((Err(ErrorInternalServerError(e)), None)),
+            }
+        }
+    })
+    .boxed_local()
+}
+
+fn parse_range(range: &str, len: u64) -> Option<(u64, u64)> {
+    let range = range.trim().strip_prefix("bytes=")?;
+    if range.contains(',') || len == 0 {
+        return None;
+    }
+    let mut parts = range.splitn(2, '-');
+    let (start, end) = (parts.next()?.trim(), parts.next()?.trim());
+    let (start, end) = match (start.is_empty(), end.is_empty()) {
+        (true, false) => {
+            let suffix = end.parse::<u64>().ok()?.min(len);
+            (len - suffix, len - 1)
+        }
+        (false, true) => (start.parse::<u64>().ok()?, len - 1),
+        (false, false) => {
+            let end = end.parse::<u64>().ok()?.min(len - 1);
+            (start.parse::<u64>().ok()?, end)
+        }
+        (true, true) => return None,
+    };
+    if start > end || start >= len {
+        return None;
+    }
+    Some((start, end))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::write::GzEncoder as GzWriter;
+    use std::io::Write;
+    use uuid::Uuid;
+
+    #[test]
+    fn parse_range_reads_start_and_end() {
+        assert_eq!(parse_range("bytes=0-9", 100), Some((0, 9)));
+        assert_eq!(parse_range("bytes=10-", 100), Some((10, 99)));
+    }
+
+    #[test]
+    fn parse_range_reads_suffix_ranges() {
+        assert_eq!(parse_range("bytes=-10", 100), Some((90, 99)));
+        assert_eq!(parse_range("bytes=-500", 100), Some((0, 99)));
+    }
+
+    #[test]
+    fn parse_range_clamps_the_end_to_the_length() {
+        assert_eq!(parse_range("bytes=90-500", 100), Some((90, 99)));
+    }
+
+    #[test]
+    fn parse_range_rejects_unsatisfiable_ranges() {
+        assert_eq!(parse_range("bytes=0-9", 0), None);
+        assert_eq!(parse_range("bytes=-10", 0), None);
+        assert_eq!(parse_range("bytes=100-", 100), None);
+        assert_eq!(parse_range("bytes=20-10", 100), None);
+        assert_eq!(parse_range("bytes=0-1,5-6", 100), None);
+        assert_eq!(parse_range("lines=0-9", 100), None);
+        assert_eq!(parse_range("bytes=-", 100), None);
+    }
+
+    #[test]
+    fn open_gz_reads_ranges_of_the_decompressed_log() {
+        let path = std::env::temp_dir()
+            .join(format!("bld-logs-{}.gz", Uuid::new_v4()))
+            .display()
+            .to_string();
+        let mut encoder = GzWriter::new(File::create(&path).unwrap(), Compression::default());
+        encoder.write_all(b"first\nsecond\nthird\n").unwrap();
+        encoder.finish().unwrap();
+
+        let body = open_gz(&path, Some("bytes=-6"), false);
+        let _ = std::fs::remove_file(&path);
+        match body {
+            Ok(LogBody::Partial {
+                mut reader,
+                start,
+                end,
+                len,
+            }) => {
+                let mut content = String::new();
+                reader.read_to_string(&mut content).unwrap();
+                assert_eq!((start, end, len), (13, 18, 19));
+                assert_eq!(content, "third\n");
+            }
+            _ => panic!("expected a partial body"),
+        }
+    }
+}
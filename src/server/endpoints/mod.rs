@@ -1,15 +1,29 @@
 mod auth_redirect;
 mod hist;
 mod home;
+mod hooks;
 mod inspect;
 mod list;
+mod logs;
+mod plan;
 mod push;
+mod queue;
+mod rerun;
+mod schedules;
+mod search;
 mod stop;
 
 pub use auth_redirect::*;
 pub use hist::*;
 pub use home::*;
+pub use hooks::*;
 pub use inspect::*;
 pub use list::*;
+pub use logs::*;
+pub use plan::*;
 pub use push::*;
+pub use queue::*;
+pub use rerun::*;
+pub use schedules::*;
+pub use search::*;
 pub use stop::*;
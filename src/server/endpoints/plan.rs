@@ -0,0 +1,22 @@
+use crate::run::{Pipeline, Plan};
+use crate::server::User;
+use crate::types::ExecInfo;
+use actix_web::{post, web, HttpResponse};
+use std::collections::HashMap;
+
+#[post("/plan")]
+pub async fn plan(user: Option<User>, info: web::Json<ExecInfo>) -> HttpResponse {
+    if user.is_none() {
+        return HttpResponse::Unauthorized().body("");
+    }
+
+    let info = info.into_inner();
+    if let Err(e) = Pipeline::validate_name(&info.name) {
+        return HttpResponse::BadRequest().body(e.to_string());
+    }
+    let vars = info.variables.unwrap_or_else(HashMap::new);
+    match Plan::from_file(&info.name, &vars, info.files.as_ref()) {
+        Ok(plan) => HttpResponse::Ok().body(plan),
+        Err(e) => HttpResponse::BadRequest().body(e.to_string()),
+    }
+}
@@ -0,0 +1,37 @@
+use crate::server::{PipelineQueue, User};
+use actix_web::{get, post, web, HttpResponse, Responder};
+
+#[get("/queue")]
+pub async fn queue((user, data): (Option<User>, web::Data<PipelineQueue>)) -> impl Responder {
+    if user.is_none() {
+        return HttpResponse::Unauthorized().body("");
+    }
+
+    let mut info = format!("RUNNING: {}/{}\n", data.running(), data.max_concurrent_runs);
+    for worker in data.workers().iter() {
+        info.push_str(&format!("\n{}\n", worker.to_string()));
+    }
+    for run in data.list().iter() {
+        info.push_str(&format!("\n{}\n", run.to_string()));
+    }
+    HttpResponse::Ok().body(info)
+}
+
+#[post("/cancel")]
+pub fn cancel(
+    (user, req, data): (Option<User>, web::Json<String>, web::Data<PipelineQueue>),
+) -> HttpResponse {
+    if user.is_none() {
+        return HttpResponse::Unauthorized().body("");
+    }
+
+    let id = req.into_inner();
+    match data.cancel(&id) {
+        Some(Ok(_)) => HttpResponse::Ok().finish(),
+        Some(Err(e)) => HttpResponse::BadRequest().body(e.to_string()),
+        None => {
+            let message = format!("no queued pipeline with id {} found", &id);
+            HttpResponse::BadRequest().body(message)
+        }
+    }
+}
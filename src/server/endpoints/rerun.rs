@@ -0,0 +1,62 @@
+use crate::config::BldConfig;
+use crate::persist::Database;
+use crate::server::{Leadership, PipelineInfo, PipelinePool, PipelineQueue, User};
+use crate::types::{BldError, ExecInfo, RerunInfo, Result, RunTrigger};
+use actix_web::{post, web, HttpResponse};
+
+#[post("/rerun")]
+pub async fn rerun(
+    user: Option<User>,
+    info: web::Json<RerunInfo>,
+    config: web::Data<BldConfig>,
+    pool: web::Data<PipelinePool>,
+    queue: web::Data<PipelineQueue>,
+    leadership: web::Data<Leadership>,
+) -> HttpResponse {
+    let user = match user {
+        Some(user) => user,
+        None => return HttpResponse::Unauthorized().body(""),
+    };
+    if !leadership.is_leader() {
+        return HttpResponse::ServiceUnavailable().body("server is on standby");
+    }
+
+    match start(config, pool, &queue, &user, &info.into_inner()) {
+        Ok(id) => HttpResponse::Ok().body(id),
+        Err(e) => HttpResponse::BadRequest().body(e.to_string()),
+    }
+}
+
+fn start(
+    config: web::Data<BldConfig>,
+    pool: web::Data<PipelinePool>,
+    queue: &web::Data<PipelineQueue>,
+    user: &User,
+    data: &RerunInfo,
+) -> Result<String> {
+    let mut db = Database::connect(&config.local.db)?;
+    db.load(&data.id);
+    let pipeline = match &db.pipeline {
+        Some(pipeline) => pipeline,
+        None => return Err(BldError::Other("pipeline not found".to_string())),
+    };
+    let mut info = ExecInfo::new(&pipeline.name, Some(pipeline.variable_map()?), None);
+    if !data.latest_pipeline {
+        let files = db.files(&data.id)?;
+        if files.is_empty() {
+            let message = "no pipeline snapshot found for run, use the latest pipeline instead";
+            return Err(BldError::Other(message.to_string()));
+        }
+        info.files = Some(files);
+    }
+    let info = PipelineInfo::new(
+        config.clone(),
+        pool,
+        &user.name,
+        info,
+        RunTrigger::Rerun(data.id.clone()),
+    )?;
+    let id = info.id.clone();
+    PipelineQueue::enqueue(queue, info);
+    Ok(id)
+}
@@ -0,0 +1,23 @@
+use crate::config::BldConfig;
+use crate::server::User;
+use actix_web::{get, web, HttpResponse, Responder};
+
+#[get("/schedules")]
+pub async fn schedules((user, config): (Option<User>, web::Data<BldConfig>)) -> impl Responder {
+    if user.is_none() {
+        return HttpResponse::Unauthorized().body("");
+    }
+
+    let mut info = String::new();
+    for schedule in config.local.schedules.iter() {
+        let next = schedule
+            .next()
+            .map(|n| n.to_string())
+            .unwrap_or_else(|| String::from("never"));
+        info.push_str(&format!("\nPIPELINE: {}\n", schedule.pipeline));
+        info.push_str(&format!("CRON: {}\n", schedule.cron));
+        info.push_str(&format!("TIMEZONE: {}\n", schedule.timezone.name()));
+        info.push_str(&format!("NEXT RUN: {}\n", next));
+    }
+    HttpResponse::Ok().body(info)
+}
@@ -0,0 +1,28 @@
+use crate::config::BldConfig;
+use crate::search::search_info;
+use crate::server::{PipelinePool, User};
+use crate::types::SearchInfo;
+use actix_web::{post, web, HttpResponse};
+
+#[post("/search")]
+pub async fn search(
+    user: Option<User>,
+    info: web::Json<SearchInfo>,
+    config: web::Data<BldConfig>,
+    pool: web::Data<PipelinePool>,
+) -> HttpResponse {
+    if user.is_none() {
+        return HttpResponse::Unauthorized().body("");
+    }
+
+    let info = info.into_inner();
+    let result = pool
+        .runtime
+        .spawn_blocking(move || search_info(config.get_ref(), &info))
+        .await;
+    match result {
+        Ok(Ok(results)) => HttpResponse::Ok().json(results),
+        Ok(Err(e)) => HttpResponse::BadRequest().body(e.to_string()),
+        Err(e) => HttpResponse::InternalServerError().body(e.to_string()),
+    }
+}
@@ -1,18 +1,28 @@
-use crate::server::{PipelinePool, User};
+use crate::server::{PipelinePool, PipelineQueue, User};
 use actix_web::{post, web, HttpResponse};
 
 #[post("/stop")]
 pub fn stop(
-    (user, req, data): (Option<User>, web::Json<String>, web::Data<PipelinePool>),
+    (user, req, data, queue): (
+        Option<User>,
+        web::Json<String>,
+        web::Data<PipelinePool>,
+        web::Data<PipelineQueue>,
+    ),
 ) -> HttpResponse {
     if user.is_none() {
         return HttpResponse::Unauthorized().body("");
     }
 
     let id = req.into_inner();
+    match queue.cancel(&id) {
+        Some(Ok(_)) => return HttpResponse::Ok().finish(),
+        Some(Err(e)) => return HttpResponse::BadRequest().body(e.to_string()),
+        None => {}
+    }
     let pool = data.senders.lock().unwrap();
     match pool.get(&id) {
-        Some(sender) => match sender.send(true) {
+        Some(sender) => match sender.broadcast(true) {
             Ok(_) => HttpResponse::Ok().finish(),
             Err(e) => HttpResponse::BadRequest().body(e.to_string()),
         },
@@ -1,33 +1,70 @@
 use crate::config::BldConfig;
 use crate::helpers::term::print_info;
 use crate::server::{
-    auth_redirect, hist, home, inspect, list, push, stop, ws_exec, ws_monit, PipelinePool,
+    auth_redirect, cancel, hist, hist_run, home, hooks, inspect, list, logs, plan, push, queue,
+    rerun, schedules, search, stop, ws_exec, ws_monit, ws_worker, Leadership, PipelinePool,
+    PipelineQueue, RetentionJob, ScheduledPipeline,
 };
 use crate::types::Result;
 use actix::{Arbiter, System};
 use actix_web::{middleware, web, App, HttpServer};
 use clap::ArgMatches;
+use tokio::runtime::{Builder, Handle};
 
-async fn start(config: BldConfig, host: &str, port: i64) -> Result<()> {
+async fn start(config: BldConfig, runtime: Handle, host: &str, port: i64) -> Result<()> {
     print_info(&format!("starting bld server at {}:{}", host, port))?;
+    let queue_data = web::Data::new(PipelineQueue::new(
+        config.local.max_concurrent_runs,
+        config.local.server_runs,
+    ));
     let config_data = web::Data::new(config);
-    let pool_data = web::Data::new(PipelinePool::new());
+    let pool_data = web::Data::new(PipelinePool::new(runtime));
+    let leadership_data = web::Data::new(Leadership::new());
+    Leadership::spawn(
+        leadership_data.clone(),
+        config_data.clone(),
+        pool_data.clone(),
+        queue_data.clone(),
+    );
+    ScheduledPipeline::spawn_all(
+        config_data.clone(),
+        pool_data.clone(),
+        queue_data.clone(),
+        leadership_data.clone(),
+    );
+    RetentionJob::spawn(
+        config_data.clone(),
+        pool_data.clone(),
+        leadership_data.clone(),
+    );
     std::env::set_var("RUST_LOG", "actix_server=info,actix_wev=info");
     env_logger::init();
     HttpServer::new(move || {
         App::new()
             .app_data(pool_data.clone())
+            .app_data(queue_data.clone())
             .app_data(config_data.clone())
+            .app_data(leadership_data.clone())
             .wrap(middleware::Logger::default())
             .service(home)
             .service(auth_redirect)
             .service(hist)
+            .service(hist_run)
             .service(list)
             .service(push)
             .service(stop)
+            .service(queue)
+            .service(cancel)
+            .service(schedules)
+            .service(hooks)
+            .service(rerun)
+            .service(plan)
             .service(inspect)
+            .service(logs)
+            .service(search)
             .service(web::resource("/ws-exec/").route(web::get().to(ws_exec)))
             .service(web::resource("/ws-monit").route(web::get().to(ws_monit)))
+            .service(web::resource("/ws-worker").route(web::get().to(ws_worker)))
     })
     .bind(format!("{}:{}", host, port))?
     .run()
@@ -36,9 +73,15 @@ async fn start(config: BldConfig, host: &str, port: i64) -> Result<()> {
 }
 
 pub fn sys_spawn(config: BldConfig, host: String, port: i64) -> Result<()> {
+    let runtime = Builder::new()
+        .threaded_scheduler()
+        .enable_all()
+        .thread_name("bld-runner")
+        .build()?;
+    let handle = runtime.handle().clone();
     let system = System::new("bld-server");
     Arbiter::spawn(async move {
-        let _ = start(config, &host, port).await;
+        let _ = start(config, handle, &host, port).await;
     });
     system.run()?;
     Ok(())
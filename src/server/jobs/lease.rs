@@ -0,0 +1,144 @@
+use crate::config::definitions::SERVER_LEASE_NAME;
+use crate::config::BldConfig;
+use crate::helpers::term;
+use crate::persist::Database;
+use crate::server::{PipelineInfo, PipelinePool, PipelineQueue};
+use crate::types::{ExecInfo, Result, RunTrigger};
+use actix::Arbiter;
+use actix_web::web;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+pub struct Leadership {
+    leader: AtomicBool,
+}
+
+impl Leadership {
+    pub fn new() -> Self {
+        Self {
+            leader: AtomicBool::new(false),
+        }
+    }
+
+    pub fn is_leader(&self) -> bool {
+        self.leader.load(Ordering::SeqCst)
+    }
+
+    pub fn spawn(
+        leadership: web::Data<Self>,
+        config: web::Data<BldConfig>,
+        pool: web::Data<PipelinePool>,
+        queue: web::Data<PipelineQueue>,
+    ) {
+        let election = LeaderElection {
+            leadership,
+            config,
+            pool,
+            queue,
+        };
+        Arbiter::spawn(election.run());
+    }
+}
+
+struct LeaderElection {
+    leadership: web::Data<Leadership>,
+    config: web::Data<BldConfig>,
+    pool: web::Data<PipelinePool>,
+    queue: web::Data<PipelineQueue>,
+}
+
+impl LeaderElection {
+    fn acquire(&self, duration: u64) -> Result<Option<String>> {
+        let db = Database::connect(&self.config.local.db)?;
+        db.acquire_lease(SERVER_LEASE_NAME, &self.pool.instance, duration)
+    }
+
+    fn recover(&self, previous: Option<&str>, requeue: bool) -> Result<()> {
+        let db = Database::connect(&self.config.local.db)?;
+        let orphaned = match previous {
+            Some(holder) if holder.is_empty() || holder == self.pool.instance => Vec::new(),
+            Some(holder) => db.abandoned(holder)?,
+            None => db.orphaned(&self.pool.instance)?,
+        };
+        for run in orphaned {
+            db.interrupt(&run.id)?;
+            term::print_info(&format!("marked orphaned run {} as interrupted", run.id))?;
+            if !requeue {
+                continue;
+            }
+            let files = db.files(&run.id)?;
+            if files.is_empty() {
+                let message = format!(
+                    "skipping requeue of run {} since it has no snapshot",
+                    run.id
+                );
+                term::print_info(&message)?;
+                continue;
+            }
+            let mut info = ExecInfo::new(&run.name, Some(run.variable_map()?), None);
+            info.files = Some(files);
+            let info = PipelineInfo::new(
+                self.config.clone(),
+                self.pool.clone(),
+                &run.user,
+                info,
+                RunTrigger::Rerun(run.id.clone()),
+            )?;
+            term::print_info(&format!("requeued run {} as {}", run.id, info.id))?;
+            PipelineQueue::enqueue(&self.queue, info);
+        }
+        Ok(())
+    }
+
+    fn promote(&self, previous: Option<&str>, requeue: bool) {
+        self.leadership.leader.store(true, Ordering::SeqCst);
+        if let Err(e) = self.recover(previous, requeue) {
+            let _ = term::print_error(&e.to_string());
+        }
+    }
+
+    fn demote(&self, message: &str) {
+        let _ = term::print_info(message);
+        self.leadership.leader.store(false, Ordering::SeqCst);
+        self.queue.cancel_all();
+        let senders = self.pool.senders.lock().unwrap();
+        for sender in senders.values() {
+            let _ = sender.broadcast(true);
+        }
+    }
+
+    async fn run(self) {
+        let ha = match &self.config.local.high_availability {
+            Some(ha) => ha,
+            None => return self.promote(None, false),
+        };
+        let interval = Duration::from_secs((ha.lease_duration / 3).max(1));
+        let lease = Duration::from_secs(ha.lease_duration);
+        let mut renewed: Option<Instant> = None;
+        loop {
+            let previous = match self.acquire(ha.lease_duration) {
+                Ok(previous) => previous,
+                Err(e) => {
+                    let _ = term::print_error(&e.to_string());
+                    let expired = renewed.map(|r| r.elapsed() >= lease).unwrap_or(true);
+                    if expired && self.leadership.is_leader() {
+                        self.demote("couldn't renew leader lease, this server is now on standby");
+                    }
+                    tokio::time::delay_for(interval).await;
+                    continue;
+                }
+            };
+            let leader = previous.is_some();
+            if leader {
+                renewed = Some(Instant::now());
+            }
+            if leader && !self.leadership.is_leader() {
+                let _ = term::print_info("acquired leader lease, this server is now active");
+                self.promote(previous.as_deref(), ha.requeue_interrupted);
+            } else if !leader && self.leadership.is_leader() {
+                self.demote("lost leader lease, this server is now on standby");
+            }
+            tokio::time::delay_for(interval).await;
+        }
+    }
+}
@@ -0,0 +1,7 @@
+mod lease;
+mod retention;
+mod schedule;
+
+pub use lease::*;
+pub use retention::*;
+pub use schedule::*;
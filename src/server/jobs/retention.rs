@@ -0,0 +1,68 @@
+use crate::config::BldConfig;
+use crate::helpers::term;
+use crate::persist::Retention;
+use crate::server::{Leadership, PipelinePool};
+use crate::types::Result;
+use actix_web::web;
+use std::time::Duration;
+use tokio::runtime::Handle;
+
+pub struct RetentionJob {
+    config: web::Data<BldConfig>,
+    leadership: web::Data<Leadership>,
+    runtime: Handle,
+}
+
+impl RetentionJob {
+    pub fn spawn(
+        config: web::Data<BldConfig>,
+        pool: web::Data<PipelinePool>,
+        leadership: web::Data<Leadership>,
+    ) {
+        if config.local.retention.is_none() {
+            return;
+        }
+        let job = Self {
+            config,
+            leadership,
+            runtime: pool.runtime.clone(),
+        };
+        pool.runtime.spawn(job.run());
+    }
+
+    fn prune(config: &BldConfig) -> Result<()> {
+        let retention = Retention::new(config)?;
+        let actions = retention.plan()?;
+        if actions.is_empty() {
+            return Ok(());
+        }
+        retention.apply(&actions)?;
+        term::print_info(&format!("retention applied {} actions", actions.len()))
+    }
+
+    async fn run(self) {
+        let interval = match &self.config.local.retention {
+            Some(retention) => Duration::from_secs(retention.interval),
+            None => return,
+        };
+        loop {
+            if self.leadership.is_leader() {
+                let config = self.config.clone();
+                let result = self
+                    .runtime
+                    .spawn_blocking(move || RetentionJob::prune(config.get_ref()))
+                    .await;
+                match result {
+                    Ok(Err(e)) => {
+                        let _ = term::print_error(&e.to_string());
+                    }
+                    Err(e) => {
+                        let _ = term::print_error(&e.to_string());
+                    }
+                    _ => {}
+                }
+            }
+            tokio::time::delay_for(interval).await;
+        }
+    }
+}
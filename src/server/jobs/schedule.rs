@@ -0,0 +1,82 @@
+use crate::config::definitions::SYSTEM_USER;
+use crate::config::{BldConfig, BldScheduleConfig};
+use crate::helpers::term;
+use crate::persist::Database;
+use crate::server::{Leadership, PipelineInfo, PipelinePool, PipelineQueue};
+use crate::types::{ExecInfo, Result, RunTrigger};
+use actix::Arbiter;
+use actix_web::web;
+
+pub struct ScheduledPipeline {
+    index: usize,
+    config: web::Data<BldConfig>,
+    pool: web::Data<PipelinePool>,
+    queue: web::Data<PipelineQueue>,
+    leadership: web::Data<Leadership>,
+}
+
+impl ScheduledPipeline {
+    pub fn spawn_all(
+        config: web::Data<BldConfig>,
+        pool: web::Data<PipelinePool>,
+        queue: web::Data<PipelineQueue>,
+        leadership: web::Data<Leadership>,
+    ) {
+        for index in 0..config.local.schedules.len() {
+            let job = Self {
+                index,
+                config: config.clone(),
+                pool: pool.clone(),
+                queue: queue.clone(),
+                leadership: leadership.clone(),
+            };
+            Arbiter::spawn(job.run());
+        }
+    }
+
+    fn schedule(&self) -> &BldScheduleConfig {
+        &self.config.local.schedules[self.index]
+    }
+
+    fn is_running(&self) -> Result<bool> {
+        let db = Database::connect(&self.config.local.db)?;
+        Ok(!db.active(&self.schedule().pipeline)?.is_empty())
+    }
+
+    fn trigger(&self) -> Result<()> {
+        let schedule = self.schedule();
+        if !self.leadership.is_leader() {
+            return Ok(());
+        }
+        if self.is_running()? {
+            let message = format!(
+                "skipping scheduled run of {} since the previous one is still active",
+                schedule.pipeline
+            );
+            return term::print_info(&message);
+        }
+        let info = PipelineInfo::new(
+            self.config.clone(),
+            self.pool.clone(),
+            SYSTEM_USER,
+            ExecInfo::new(&schedule.pipeline, Some(schedule.variables.clone()), None),
+            RunTrigger::Schedule,
+        )?;
+        PipelineQueue::enqueue(&self.queue, info);
+        Ok(())
+    }
+
+    async fn run(self) {
+        let mut next = self.schedule().next();
+        while let Some(date_time) = next {
+            tokio::time::delay_for(BldScheduleConfig::delay(&date_time)).await;
+            if let Err(e) = self.trigger() {
+                let _ = term::print_error(&e.to_string());
+            }
+            next = match self.schedule().next() {
+                Some(upcoming) if upcoming > date_time => Some(upcoming),
+                _ => self.schedule().after(&date_time),
+            };
+        }
+    }
+}
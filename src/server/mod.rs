@@ -2,6 +2,7 @@ mod cli;
 mod endpoints;
 mod exec;
 mod extractors;
+mod jobs;
 mod sockets;
 mod state;
 
@@ -9,5 +10,6 @@ pub use cli::*;
 pub use endpoints::*;
 pub use exec::*;
 pub use extractors::*;
+pub use jobs::*;
 pub use sockets::*;
 pub use state::*;
@@ -1,77 +1,41 @@
 use crate::config::BldConfig;
-use crate::helpers::term;
-use crate::path;
-use crate::persist::{Database, FileLogger, FileScanner, Scanner};
-use crate::run::{Pipeline, Runner};
-use crate::server::{PipelinePool, User};
-use crate::types::{BldError, ExecInfo, Result};
+use crate::persist::{FileScanner, Scanner};
+use crate::server::{Leadership, PipelineInfo, PipelinePool, PipelineQueue, User};
+use crate::types::{BldError, ExecInfo, LogEntry, Result, RunTrigger};
 use actix::prelude::*;
-use actix_web::{error::ErrorUnauthorized, web, Error, HttpRequest, HttpResponse};
+use actix_web::error::{ErrorServiceUnavailable, ErrorUnauthorized};
+use actix_web::{web, Error, HttpRequest, HttpResponse};
 use actix_web_actors::ws;
-use std::collections::HashMap;
-use std::path::PathBuf;
-use std::sync::mpsc::{self, Receiver};
-use std::sync::{Arc, Mutex};
-use std::thread;
 use std::time::{Duration, Instant};
-use tokio::runtime::Runtime;
-use uuid::Uuid;
+use tokio::sync::broadcast::RecvError;
 
 type StdResult<T, V> = std::result::Result<T, V>;
-type AtomicDb = Arc<Mutex<Database>>;
-type AtomicFs = Arc<Mutex<FileLogger>>;
-type AtomicRecv = Arc<Mutex<Receiver<bool>>>;
-
-struct PipelineInfo {
-    pool: web::Data<PipelinePool>,
-    id: String,
-    name: String,
-    ex: AtomicDb,
-    lg: AtomicFs,
-    cm: Option<AtomicRecv>,
-    vars: Arc<HashMap<String, String>>,
-}
-
-impl PipelineInfo {
-    pub fn spawn(self) {
-        thread::spawn(move || {
-            if let Ok(mut rt) = Runtime::new() {
-                rt.block_on(async move {
-                    if let Err(e) =
-                        Runner::from_file(self.name, self.ex, self.lg, self.cm, self.vars)
-                            .await
-                            .await
-                    {
-                        let _ = term::print_error(&e.to_string());
-                    }
-                    {
-                        let mut pool = self.pool.senders.lock().unwrap();
-                        pool.remove(&self.id);
-                    }
-                });
-            }
-        });
-    }
-}
 
 pub struct ExecutePipelineSocket {
     hb: Instant,
     user: User,
     config: web::Data<BldConfig>,
-    exec: Option<AtomicDb>,
     scanner: Option<FileScanner>,
+    offset: u64,
     pool: web::Data<PipelinePool>,
+    queue: web::Data<PipelineQueue>,
 }
 
 impl ExecutePipelineSocket {
-    pub fn new(user: User, config: web::Data<BldConfig>, pool: web::Data<PipelinePool>) -> Self {
+    pub fn new(
+        user: User,
+        config: web::Data<BldConfig>,
+        pool: web::Data<PipelinePool>,
+        queue: web::Data<PipelineQueue>,
+    ) -> Self {
         Self {
             hb: Instant::now(),
             user,
             config,
-            exec: None,
             scanner: None,
+            offset: 0,
             pool,
+            queue,
         }
     }
 
@@ -84,67 +48,44 @@ impl ExecutePipelineSocket {
         ctx.ping(b"");
     }
 
-    fn scan(act: &mut Self, ctx: &mut <Self as Actor>::Context) {
-        if let Some(scanner) = act.scanner.as_mut() {
-            let content = scanner.fetch();
-            for line in content.iter() {
-                ctx.text(line);
+    fn send(act: &mut Self, ctx: &mut <Self as Actor>::Context, entry: &LogEntry) {
+        if let Some(offset) = entry.offset {
+            if offset <= act.offset {
+                return;
             }
+            act.offset = offset;
+        }
+        if let Ok(text) = serde_json::to_string(entry) {
+            ctx.text(text);
         }
     }
 
-    fn exec(act: &mut Self, ctx: &mut <Self as Actor>::Context) {
-        if let Some(exec) = act.exec.as_mut() {
-            let exec = exec.lock().unwrap();
-            if let Some(pipeline) = &exec.pipeline {
-                if !pipeline.running {
-                    ctx.stop();
-                }
-            }
+    fn scan(act: &mut Self, ctx: &mut <Self as Actor>::Context, last: bool) {
+        let content = match act.scanner.as_mut() {
+            Some(scanner) if last => scanner.finish(),
+            Some(scanner) => scanner.fetch(),
+            None => return,
+        };
+        for entry in content.iter() {
+            ExecutePipelineSocket::send(act, ctx, entry);
         }
     }
 
-    fn get_info(&mut self, data: &str) -> Result<PipelineInfo> {
+    fn get_info(&mut self, data: &str, ctx: &mut <Self as Actor>::Context) -> Result<PipelineInfo> {
         let info = serde_json::from_str::<ExecInfo>(data)?;
-        let path = Pipeline::get_path(&info.name)?;
-        if !path.is_file() {
-            let message = String::from("pipeline file not found");
-            return Err(BldError::IoError(message));
-        }
-
-        let id = Uuid::new_v4().to_string();
-        let config = self.config.get_ref();
-        let logs = path![&config.local.logs, format!("{}-{}", &info.name, id)]
-            .display()
-            .to_string();
-
-        let mut db = Database::connect(&config.local.db)?;
-        db.add(&id, &info.name, &self.user.name)?;
-
-        let ex = Arc::new(Mutex::new(db));
-        let (tx, rx) = mpsc::channel::<bool>();
-        let rx = Arc::new(Mutex::new(rx));
-        {
-            let mut pool = self.pool.senders.lock().unwrap();
-            pool.insert(id.clone(), tx);
-        }
-
-        let info = PipelineInfo {
-            pool: self.pool.clone(),
-            id,
-            name: info.name,
-            ex,
-            lg: Arc::new(Mutex::new(FileLogger::new(&logs)?)),
-            cm: Some(rx),
-            vars: match info.variables {
-                Some(vars) => Arc::new(vars),
-                None => Arc::new(HashMap::<String, String>::new()),
-            },
+        let info = PipelineInfo::new(
+            self.config.clone(),
+            self.pool.clone(),
+            &self.user.name,
+            info,
+            RunTrigger::Manual,
+        )?;
+        let receiver = match self.pool.hub.subscribe(&info.id) {
+            Some(receiver) => receiver,
+            None => return Err(BldError::Other("run output is not available".to_string())),
         };
-
-        self.exec = Some(info.ex.clone());
-        self.scanner = Some(FileScanner::new(&logs)?);
-
+        self.scanner = Some(FileScanner::new(&info.logs)?);
+        ctx.add_stream(receiver.into_stream());
         Ok(info)
     }
 }
@@ -153,24 +94,35 @@ impl Actor for ExecutePipelineSocket {
     type Context = ws::WebsocketContext<Self>;
 
     fn started(&mut self, ctx: &mut Self::Context) {
-        ctx.run_interval(Duration::from_millis(500), |act, ctx| {
+        ctx.run_interval(Duration::from_secs(1), |act, ctx| {
             ExecutePipelineSocket::heartbeat(act, ctx);
-            ExecutePipelineSocket::scan(act, ctx);
-        });
-        ctx.run_interval(Duration::from_secs(10), |act, ctx| {
-            ExecutePipelineSocket::scan(act, ctx);
-            ExecutePipelineSocket::exec(act, ctx);
         });
     }
 }
 
+impl StreamHandler<StdResult<LogEntry, RecvError>> for ExecutePipelineSocket {
+    fn handle(&mut self, msg: StdResult<LogEntry, RecvError>, ctx: &mut Self::Context) {
+        match msg {
+            Ok(entry) => ExecutePipelineSocket::send(self, ctx, &entry),
+            Err(RecvError::Lagged(_)) => ExecutePipelineSocket::scan(self, ctx, false),
+            Err(RecvError::Closed) => {}
+        }
+    }
+
+    fn finished(&mut self, ctx: &mut Self::Context) {
+        ExecutePipelineSocket::scan(self, ctx, true);
+        ctx.close(Some(ws::CloseCode::Normal.into()));
+        ctx.stop();
+    }
+}
+
 impl StreamHandler<StdResult<ws::Message, ws::ProtocolError>> for ExecutePipelineSocket {
     fn handle(&mut self, msg: StdResult<ws::Message, ws::ProtocolError>, ctx: &mut Self::Context) {
         match msg {
             Ok(ws::Message::Text(txt)) => {
-                match self.get_info(&txt) {
+                match self.get_info(&txt, ctx) {
                     Ok(pipeline_info) => {
-                        pipeline_info.spawn();
+                        PipelineQueue::enqueue(&self.queue, pipeline_info);
                     }
                     Err(e) => {
                         eprintln!("{}", e.to_string());
@@ -201,10 +153,16 @@ pub async fn ws_exec(
     stream: web::Payload,
     config: web::Data<BldConfig>,
     pool: web::Data<PipelinePool>,
+    queue: web::Data<PipelineQueue>,
+    leadership: web::Data<Leadership>,
 ) -> StdResult<HttpResponse, Error> {
     let user = user.ok_or_else(|| ErrorUnauthorized(""))?;
+    if !leadership.is_leader() {
+        return Err(ErrorServiceUnavailable("server is on standby"));
+    }
     println!("{:?}", req);
-    let res = ws::start(ExecutePipelineSocket::new(user, config, pool), &req, stream);
+    let socket = ExecutePipelineSocket::new(user, config, pool, queue);
+    let res = ws::start(socket, &req, stream);
     println!("{:?}", res);
     res
 }
@@ -1,5 +1,7 @@
 mod exec;
 mod monit;
+mod worker;
 
 pub use exec::*;
 pub use monit::*;
+pub use worker::*;
@@ -1,13 +1,14 @@
 use crate::config::BldConfig;
 use crate::path;
 use crate::persist::{Database, FileScanner, Scanner};
-use crate::server::User;
-use crate::types::{BldError, MonitInfo, Result};
+use crate::server::{PipelinePool, User};
+use crate::types::{BldError, LogEntry, MonitInfo, Result};
 use actix::prelude::*;
 use actix_web::{error::ErrorUnauthorized, web, Error, HttpRequest, HttpResponse};
 use actix_web_actors::ws;
 use std::path::PathBuf;
 use std::time::{Duration, Instant};
+use tokio::sync::broadcast::RecvError;
 
 type StdResult<T, V> = std::result::Result<T, V>;
 
@@ -15,17 +16,21 @@ pub struct MonitorPipelineSocket {
     hb: Instant,
     id: String,
     config: web::Data<BldConfig>,
+    pool: web::Data<PipelinePool>,
     scanner: Option<FileScanner>,
+    offset: u64,
     db: Option<Database>,
 }
 
 impl MonitorPipelineSocket {
-    pub fn new(config: web::Data<BldConfig>) -> Self {
+    pub fn new(config: web::Data<BldConfig>, pool: web::Data<PipelinePool>) -> Self {
         Self {
             config,
+            pool,
             hb: Instant::now(),
             id: String::new(),
             scanner: None,
+            offset: 0,
             db: None,
         }
     }
@@ -39,33 +44,54 @@ impl MonitorPipelineSocket {
         ctx.ping(b"");
     }
 
-    fn scan(act: &mut Self, ctx: &mut <Self as Actor>::Context) {
-        if let Some(scanner) = act.scanner.as_mut() {
-            let content = scanner.fetch();
-            for line in content.iter() {
-                ctx.text(line);
+    fn send(act: &mut Self, ctx: &mut <Self as Actor>::Context, entry: &LogEntry) {
+        if let Some(offset) = entry.offset {
+            if offset <= act.offset {
+                return;
             }
+            act.offset = offset;
+        }
+        if let Ok(text) = serde_json::to_string(entry) {
+            ctx.text(text);
+        }
+    }
+
+    fn scan(act: &mut Self, ctx: &mut <Self as Actor>::Context, last: bool) {
+        let content = match act.scanner.as_mut() {
+            Some(scanner) if last => scanner.finish(),
+            Some(scanner) => scanner.fetch(),
+            None => return,
+        };
+        for entry in content.iter() {
+            MonitorPipelineSocket::send(act, ctx, entry);
         }
     }
 
+    fn close(act: &mut Self, ctx: &mut <Self as Actor>::Context) {
+        MonitorPipelineSocket::scan(act, ctx, true);
+        ctx.close(Some(ws::CloseCode::Normal.into()));
+        ctx.stop();
+    }
+
     fn exec(act: &mut Self, ctx: &mut <Self as Actor>::Context) {
         if let Some(db) = act.db.as_mut() {
             db.load(&act.id);
             match &db.pipeline {
                 Some(pipeline) => {
-                    if !pipeline.running {
-                        ctx.stop();
+                    if !pipeline.running && !pipeline.is_queued() {
+                        MonitorPipelineSocket::close(act, ctx);
                     }
                 }
                 None => {
                     ctx.text("internal server error");
+                    ctx.close(Some(ws::CloseCode::Error.into()));
                     ctx.stop();
                 }
             }
         }
     }
 
-    fn dependencies(&mut self, data: &str) -> Result<()> {
+    fn dependencies(&mut self, data: &str, ctx: &mut <Self as Actor>::Context) -> Result<()> {
         let data = serde_json::from_str::<MonitInfo>(data)?;
         let config = self.config.get_ref();
         let mut db = Database::connect(&config.local.db)?;
@@ -86,6 +112,8 @@ impl MonitorPipelineSocket {
         };
 
         self.id = pipeline.id.clone();
+        ctx.binary(self.id.clone());
+        let active = pipeline.running || pipeline.is_queued();
 
         let path = path![
             &config.local.logs,
@@ -94,8 +122,18 @@ impl MonitorPipelineSocket {
         .display()
         .to_string();
 
-        self.scanner = Some(FileScanner::new(&path)?);
-        self.db = Some(db);
+        let receiver = self.pool.hub.subscribe(&self.id);
+        self.offset = data.from_offset.unwrap_or(0);
+        self.scanner = Some(FileScanner::from_offset(&path, self.offset)?);
+        MonitorPipelineSocket::scan(self, ctx, false);
+
+        match receiver {
+            Some(receiver) => {
+                ctx.add_stream(receiver.into_stream());
+            }
+            None if active => self.db = Some(db),
+            None => MonitorPipelineSocket::close(self, ctx),
+        }
         Ok(())
     }
 }
@@ -106,21 +144,36 @@ impl Actor for MonitorPipelineSocket {
     fn started(&mut self, ctx: &mut Self::Context) {
         ctx.run_interval(Duration::from_secs(1), |act, ctx| {
             MonitorPipelineSocket::heartbeat(act, ctx);
-            MonitorPipelineSocket::scan(act, ctx);
-        });
-        ctx.run_interval(Duration::from_secs(1), |act, ctx| {
-            MonitorPipelineSocket::exec(act, ctx);
+            if act.db.is_some() {
+                MonitorPipelineSocket::scan(act, ctx, false);
+                MonitorPipelineSocket::exec(act, ctx);
+            }
         });
     }
 }
 
+impl StreamHandler<StdResult<LogEntry, RecvError>> for MonitorPipelineSocket {
+    fn handle(&mut self, msg: StdResult<LogEntry, RecvError>, ctx: &mut Self::Context) {
+        match msg {
+            Ok(entry) => MonitorPipelineSocket::send(self, ctx, &entry),
+            Err(RecvError::Lagged(_)) => MonitorPipelineSocket::scan(self, ctx, false),
+            Err(RecvError::Closed) => {}
+        }
+    }
+
+    fn finished(&mut self, ctx: &mut Self::Context) {
+        MonitorPipelineSocket::close(self, ctx);
+    }
+}
+
 impl StreamHandler<StdResult<ws::Message, ws::ProtocolError>> for MonitorPipelineSocket {
     fn handle(&mut self, msg: StdResult<ws::Message, ws::ProtocolError>, ctx: &mut Self::Context) {
         match msg {
             Ok(ws::Message::Text(txt)) => {
-                if let Err(e) = self.dependencies(&txt) {
+                if let Err(e) = self.dependencies(&txt, ctx) {
                     eprintln!("{}", e.to_string());
                     ctx.text("internal server error");
+                    ctx.close(Some(ws::CloseCode::Error.into()));
                     ctx.stop();
                 }
             }
@@ -145,13 +198,14 @@ pub async fn ws_monit(
     req: HttpRequest,
     stream: web::Payload,
     config: web::Data<BldConfig>,
+    pool: web::Data<PipelinePool>,
 ) -> StdResult<HttpResponse, Error> {
     if user.is_none() {
         return Err(ErrorUnauthorized(""));
     }
 
     println!("{:?}", req);
-    let res = ws::start(MonitorPipelineSocket::new(config), &req, stream);
+    let res = ws::start(MonitorPipelineSocket::new(config, pool), &req, stream);
     println!("{:?}", res);
     res
 }
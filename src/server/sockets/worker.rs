@@ -0,0 +1,117 @@
+use crate::server::{Leadership, PipelineQueue, User};
+use crate::types::{Result, WorkerMessage};
+use actix::prelude::*;
+use actix_web::error::{ErrorServiceUnavailable, ErrorUnauthorized};
+use actix_web::{web, Error, HttpRequest, HttpResponse};
+use actix_web_actors::ws;
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+type StdResult<T, V> = std::result::Result<T, V>;
+
+pub struct WorkerSocket {
+    hb: Instant,
+    id: String,
+    queue: web::Data<PipelineQueue>,
+}
+
+impl WorkerSocket {
+    pub fn new(queue: web::Data<PipelineQueue>) -> Self {
+        Self {
+            hb: Instant::now(),
+            id: Uuid::new_v4().to_string(),
+            queue,
+        }
+    }
+
+    fn heartbeat(act: &Self, ctx: &mut <Self as Actor>::Context) {
+        if Instant::now().duration_since(act.hb) > Duration::from_secs(10) {
+            println!("Worker {} heartbeat failed, disconnecting!", act.id);
+            ctx.stop();
+            return;
+        }
+        ctx.ping(b"");
+    }
+
+    fn receive(&mut self, data: &str, ctx: &mut <Self as Actor>::Context) -> Result<()> {
+        match serde_json::from_str::<WorkerMessage>(data)? {
+            WorkerMessage::Register { labels, capacity } => {
+                let message = format!(
+                    "Worker {} registered with labels: {}",
+                    self.id,
+                    labels.join(", ")
+                );
+                let addr = ctx.address().recipient();
+                PipelineQueue::register(&self.queue, &self.id, labels, capacity, addr)?;
+                println!("{}", message);
+                Ok(())
+            }
+            message => PipelineQueue::receive(&self.queue, &self.id, message),
+        }
+    }
+}
+
+impl Actor for WorkerSocket {
+    type Context = ws::WebsocketContext<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        ctx.run_interval(Duration::from_secs(1), |act, ctx| {
+            WorkerSocket::heartbeat(act, ctx);
+        });
+    }
+
+    fn stopped(&mut self, _ctx: &mut Self::Context) {
+        PipelineQueue::unregister(&self.queue, &self.id);
+    }
+}
+
+impl Handler<WorkerMessage> for WorkerSocket {
+    type Result = ();
+
+    fn handle(&mut self, msg: WorkerMessage, ctx: &mut Self::Context) {
+        if let Ok(msg) = serde_json::to_string(&msg) {
+            ctx.text(msg);
+        }
+    }
+}
+
+impl StreamHandler<StdResult<ws::Message, ws::ProtocolError>> for WorkerSocket {
+    fn handle(&mut self, msg: StdResult<ws::Message, ws::ProtocolError>, ctx: &mut Self::Context) {
+        match msg {
+            Ok(ws::Message::Text(txt)) => {
+                if let Err(e) = self.receive(&txt, ctx) {
+                    eprintln!("{}", e.to_string());
+                }
+            }
+            Ok(ws::Message::Ping(msg)) => {
+                self.hb = Instant::now();
+                ctx.pong(&msg);
+            }
+            Ok(ws::Message::Pong(_)) => {
+                self.hb = Instant::now();
+            }
+            Ok(ws::Message::Close(reason)) => {
+                ctx.close(reason);
+                ctx.stop();
+            }
+            _ => ctx.stop(),
+        }
+    }
+}
+
+pub async fn ws_worker(
+    user: Option<User>,
+    req: HttpRequest,
+    stream: web::Payload,
+    queue: web::Data<PipelineQueue>,
+    leadership: web::Data<Leadership>,
+) -> StdResult<HttpResponse, Error> {
+    if user.is_none() {
+        return Err(ErrorUnauthorized(""));
+    }
+    if !leadership.is_leader() {
+        return Err(ErrorServiceUnavailable("server is on standby"));
+    }
+
+    ws::start(WorkerSocket::new(queue), &req, stream)
+}
@@ -0,0 +1,34 @@
+use crate::config::definitions::LOG_HUB_CAPACITY;
+use crate::types::LogEntry;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tokio::sync::broadcast::{self, Receiver, Sender};
+
+pub struct LogHub {
+    channels: Mutex<HashMap<String, Sender<LogEntry>>>,
+}
+
+impl LogHub {
+    pub fn new() -> Self {
+        Self {
+            channels: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn create(&self, id: &str) -> Sender<LogEntry> {
+        let (tx, _) = broadcast::channel(LOG_HUB_CAPACITY);
+        let mut channels = self.channels.lock().unwrap();
+        channels.insert(id.to_string(), tx.clone());
+        tx
+    }
+
+    pub fn subscribe(&self, id: &str) -> Option<Receiver<LogEntry>> {
+        let channels = self.channels.lock().unwrap();
+        channels.get(id).map(|tx| tx.subscribe())
+    }
+
+    pub fn remove(&self, id: &str) {
+        let mut channels = self.channels.lock().unwrap();
+        channels.remove(id);
+    }
+}
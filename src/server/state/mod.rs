@@ -1,3 +1,7 @@
+mod hub;
 mod pool;
+mod queue;
 
+pub use hub::*;
 pub use pool::*;
+pub use queue::*;
@@ -1,14 +1,23 @@
+use crate::server::LogHub;
 use std::collections::HashMap;
-use std::sync::mpsc::Sender;
 use std::sync::Mutex;
+use tokio::runtime::Handle;
+use tokio::sync::watch::Sender;
+use uuid::Uuid;
 
 pub struct PipelinePool {
+    pub runtime: Handle,
+    pub instance: String,
+    pub hub: LogHub,
     pub senders: Mutex<HashMap<String, Sender<bool>>>,
 }
 
 impl PipelinePool {
-    pub fn new() -> Self {
+    pub fn new(runtime: Handle) -> Self {
         PipelinePool {
+            runtime,
+            instance: Uuid::new_v4().to_string(),
+            hub: LogHub::new(),
             senders: Mutex::new(HashMap::new()),
         }
     }
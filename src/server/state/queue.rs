@@ -0,0 +1,562 @@
+use crate::config::definitions::TRIGGER_MAX_DEPTH;
+use crate::config::BldConfig;
+use crate::helpers::term;
+use crate::path;
+use crate::persist::{
+    ChannelLogger, CompositeLogger, Database, Execution, FileLogger, LogIndex, Logger,
+};
+use crate::run::{Pipeline, Runner};
+use crate::server::PipelinePool;
+use crate::types::{BldError, ExecInfo, Result, RunState, RunTrigger, StopReceiver, WorkerMessage};
+use actix::Recipient;
+use actix_web::web;
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use tokio::sync::watch;
+use uuid::Uuid;
+
+type AtomicDb = Arc<Mutex<Database>>;
+type AtomicLog = Arc<Mutex<ChannelLogger>>;
+
+pub struct PipelineInfo {
+    pub config: web::Data<BldConfig>,
+    pub pool: web::Data<PipelinePool>,
+    pub id: String,
+    pub name: String,
+    pub user: String,
+    pub priority: i64,
+    pub ex: AtomicDb,
+    pub lg: AtomicLog,
+    pub cm: Option<StopReceiver>,
+    pub vars: Arc<HashMap<String, String>>,
+    pub files: Arc<HashMap<String, String>>,
+    pub labels: Vec<String>,
+    pub logs: String,
+    pub depth: usize,
+}
+
+impl PipelineInfo {
+    pub fn new(
+        config: web::Data<BldConfig>,
+        pool: web::Data<PipelinePool>,
+        user: &str,
+        info: ExecInfo,
+        trigger: RunTrigger,
+    ) -> Result<Self> {
+        let name = info.name;
+        Pipeline::validate_name(&name)?;
+        let files = match info.files {
+            Some(files) if files.contains_key(&name) => files,
+            Some(_) => {
+                let message = format!("pipeline file {} not found", name);
+                return Err(BldError::IoError(message));
+            }
+            None => Pipeline::snapshot(&name)?,
+        };
+        let pip = Pipeline::parse(&files[&name])?;
+        pip.check_triggers(&name)?;
+        let labels = pip.required_labels();
+        let vars = info.variables.unwrap_or_else(HashMap::new);
+
+        let id = Uuid::new_v4().to_string();
+        let logs = path![&config.local.logs, format!("{}-{}", name, id)]
+            .display()
+            .to_string();
+
+        let mut db = Database::connect(&config.local.db)?;
+        let logging = &config.local.logging;
+        let composite = FileLogger::new(&logs).and_then(|file| {
+            let file = file.index().publish(pool.hub.create(&id));
+            CompositeLogger::new().add(file).sinks(
+                logging,
+                &config.local.logs,
+                &logging.server_sinks,
+                &name,
+                &id,
+            )
+        });
+        let composite = match composite {
+            Ok(composite) => composite,
+            Err(e) => {
+                pool.hub.remove(&id);
+                let _ = fs::remove_file(&logs);
+                let _ = fs::remove_file(LogIndex::path(&logs));
+                return Err(e);
+            }
+        };
+
+        let persisted = db
+            .add(&id, &name, user, &trigger, Some(&pool.instance))
+            .and_then(|_| db.snapshot(&files, &vars));
+        if let Err(e) = persisted {
+            pool.hub.remove(&id);
+            if db.pipeline.is_some() {
+                if let Err(e) = db.update(RunState::Failed) {
+                    let message = format!("Couldn't persist the run: {}", e.to_string());
+                    let _ = term::print_error(&message);
+                }
+            } else {
+                let _ = fs::remove_file(&logs);
+                let _ = fs::remove_file(LogIndex::path(&logs));
+            }
+            return Err(e);
+        }
+
+        let lg = ChannelLogger::atom(&pool.runtime, composite);
+        let (tx, rx) = watch::channel(false);
+        {
+            let mut senders = pool.senders.lock().unwrap();
+            senders.insert(id.clone(), tx);
+        }
+
+        Ok(Self {
+            config,
+            pool,
+            id,
+            name,
+            user: user.to_string(),
+            priority: info.priority.unwrap_or(0),
+            ex: Arc::new(Mutex::new(db)),
+            lg,
+            cm: Some(rx),
+            vars: Arc::new(vars),
+            files: Arc::new(files),
+            labels,
+            logs,
+            depth: trigger.depth(),
+        })
+    }
+
+    fn spawn(self, queue: web::Data<PipelineQueue>) {
+        let runtime = self.pool.runtime.clone();
+        runtime.spawn(async move {
+            let _running = RunningGuard(queue.clone());
+            let result = Runner::from_file(
+                self.name.clone(),
+                self.ex.clone(),
+                self.lg.clone(),
+                self.cm.clone(),
+                self.vars.clone(),
+                Some(self.files.clone()),
+            )
+            .await;
+            if let Err(e) = result {
+                self.fail(&e);
+            }
+            self.complete(&queue);
+        });
+    }
+
+    fn spawn_on_worker(&self, worker: &Recipient<WorkerMessage>) {
+        let _ = worker.do_send(WorkerMessage::Run {
+            id: self.id.clone(),
+            name: self.name.clone(),
+            variables: (*self.vars).clone(),
+            files: (*self.files).clone(),
+        });
+        if let Some(cm) = &self.cm {
+            let id = self.id.clone();
+            let mut cm = cm.clone();
+            let worker = worker.clone();
+            self.pool.runtime.spawn(async move {
+                while let Some(stop) = cm.recv().await {
+                    if stop {
+                        let _ = worker.do_send(WorkerMessage::Stop { id });
+                        break;
+                    }
+                }
+            });
+        }
+    }
+
+    fn fail(&self, error: &BldError) {
+        if let BldError::Stopped = error {
+            return;
+        }
+        let _ = term::print_error(&error.to_string());
+        {
+            let mut lg = self.lg.lock().unwrap();
+            lg.dumpln(&error.to_string());
+        }
+        let mut ex = self.ex.lock().unwrap();
+        let active = ex
+            .pipeline
+            .as_ref()
+            .map(|p| p.running || p.is_queued())
+            .unwrap_or(false);
+        if active {
+            if let Err(e) = ex.update(RunState::Failed) {
+                let mut lg = self.lg.lock().unwrap();
+                lg.error(&format!("Couldn't persist the run: {}", e.to_string()));
+            }
+        }
+    }
+
+    fn complete(&self, queue: &web::Data<PipelineQueue>) {
+        {
+            let mut pool = self.pool.senders.lock().unwrap();
+            pool.remove(&self.id);
+        }
+        self.pool.hub.remove(&self.id);
+        self.downstream(queue);
+    }
+
+    fn downstream(&self, queue: &web::Data<PipelineQueue>) {
+        let triggers = {
+            let mut ex = self.ex.lock().unwrap();
+            std::mem::take(&mut ex.triggers)
+        };
+        let depth = self.depth + 1;
+        for (name, variables) in triggers {
+            if depth > TRIGGER_MAX_DEPTH {
+                let message = format!(
+                    "Couldn't trigger pipeline {}: the trigger chain exceeds {} runs",
+                    name, TRIGGER_MAX_DEPTH
+                );
+                let _ = term::print_error(&message);
+                self.lg.lock().unwrap().error(&message);
+                continue;
+            }
+            let info = PipelineInfo::new(
+                self.config.clone(),
+                self.pool.clone(),
+                &self.user,
+                ExecInfo::new(&name, Some(variables), Some(self.priority)),
+                RunTrigger::Upstream(self.id.clone(), depth),
+            );
+            match info {
+                Ok(info) => {
+                    PipelineQueue::enqueue(queue, info);
+                }
+                Err(e) => {
+                    let message = format!("Couldn't trigger pipeline {}: {}", name, e.to_string());
+                    let _ = term::print_error(&message);
+                }
+            }
+        }
+    }
+
+    fn cancel(self) -> Result<()> {
+        {
+            let mut pool = self.pool.senders.lock().unwrap();
+            pool.remove(&self.id);
+        }
+        self.pool.hub.remove(&self.id);
+        {
+            let mut lg = self.lg.lock().unwrap();
+            lg.dumpln("Run cancelled before it was started");
+        }
+        let mut ex = self.ex.lock().unwrap();
+        ex.update(RunState::Cancelled)
+    }
+}
+
+struct RunningGuard(web::Data<PipelineQueue>);
+
+impl Drop for RunningGuard {
+    fn drop(&mut self) {
+        PipelineQueue::finish(&self.0);
+    }
+}
+
+pub struct QueuedRun {
+    pub id: String,
+    pub name: String,
+    pub user: String,
+    pub priority: i64,
+    pub position: usize,
+}
+
+impl ToString for QueuedRun {
+    fn to_string(&self) -> String {
+        let mut info = String::new();
+        info.push_str(&format!("POSITION: {}\n", self.position));
+        info.push_str(&format!("ID: {}\n", self.id));
+        info.push_str(&format!("NAME: {}\n", self.name));
+        info.push_str(&format!("USER: {}\n", self.user));
+        info.push_str(&format!("PRIORITY: {}", self.priority));
+        info
+    }
+}
+
+pub struct WorkerInfo {
+    pub id: String,
+    pub labels: Vec<String>,
+    pub capacity: usize,
+    pub running: usize,
+}
+
+impl ToString for WorkerInfo {
+    fn to_string(&self) -> String {
+        let mut info = String::new();
+        info.push_str(&format!("WORKER: {}\n", self.id));
+        info.push_str(&format!("LABELS: {}\n", self.labels.join(", ")));
+        info.push_str(&format!("RUNNING: {}/{}", self.running, self.capacity));
+        info
+    }
+}
+
+struct WorkerSlot {
+    labels: Vec<String>,
+    capacity: usize,
+    addr: Recipient<WorkerMessage>,
+    runs: HashMap<String, PipelineInfo>,
+}
+
+impl WorkerSlot {
+    fn accepts(&self, info: &PipelineInfo) -> bool {
+        self.runs.len() < self.capacity && info.labels.iter().all(|l| self.labels.contains(l))
+    }
+}
+
+struct QueueState {
+    running: usize,
+    pending: Vec<PipelineInfo>,
+    workers: HashMap<String, WorkerSlot>,
+}
+
+pub struct PipelineQueue {
+    pub max_concurrent_runs: usize,
+    pub server_runs: bool,
+    state: Mutex<QueueState>,
+}
+
+impl PipelineQueue {
+    pub fn new(max_concurrent_runs: usize, server_runs: bool) -> Self {
+        Self {
+            max_concurrent_runs,
+            server_runs,
+            state: Mutex::new(QueueState {
+                running: 0,
+                pending: Vec::new(),
+                workers: HashMap::new(),
+            }),
+        }
+    }
+
+    pub fn enqueue(queue: &web::Data<Self>, info: PipelineInfo) -> usize {
+        let id = info.id.clone();
+        {
+            let mut state = queue.state.lock().unwrap();
+            let position = state
+                .pending
+                .iter()
+                .position(|p| p.priority < info.priority)
+                .unwrap_or_else(|| state.pending.len());
+            state.pending.insert(position, info);
+        }
+        PipelineQueue::dispatch(queue);
+        let state = queue.state.lock().unwrap();
+        match state.pending.iter().position(|p| p.id == id) {
+            Some(position) => {
+                let mut lg = state.pending[position].lg.lock().unwrap();
+                lg.dumpln(&format!("Run queued at position {}", position + 1));
+                position + 1
+            }
+            None => 0,
+        }
+    }
+
+    fn dispatch(queue: &web::Data<Self>) {
+        let mut state = queue.state.lock().unwrap();
+        let mut index = 0;
+        while index < state.pending.len() {
+            let worker = state
+                .workers
+                .iter()
+                .find(|(_, w)| w.accepts(&state.pending[index]))
+                .map(|(id, _)| id.clone());
+            if let Some(worker) = worker {
+                let info = state.pending.remove(index);
+                let slot = state.workers.get_mut(&worker).unwrap();
+                {
+                    let mut lg = info.lg.lock().unwrap();
+                    lg.dumpln(&format!("Run dispatched to worker {}", worker));
+                }
+                info.spawn_on_worker(&slot.addr);
+                slot.runs.insert(info.id.clone(), info);
+                continue;
+            }
+            if queue.server_runs && state.running < queue.max_concurrent_runs {
+                state.running += 1;
+                let info = state.pending.remove(index);
+                info.spawn(queue.clone());
+                continue;
+            }
+            index += 1;
+        }
+    }
+
+    fn finish(queue: &web::Data<Self>) {
+        {
+            let mut state = queue.state.lock().unwrap();
+            state.running -= 1;
+        }
+        PipelineQueue::dispatch(queue);
+    }
+
+    pub fn running(&self) -> usize {
+        self.state.lock().unwrap().running
+    }
+
+    pub fn list(&self) -> Vec<QueuedRun> {
+        let state = self.state.lock().unwrap();
+        state
+            .pending
+            .iter()
+            .enumerate()
+            .map(|(i, p)| QueuedRun {
+                id: p.id.clone(),
+                name: p.name.clone(),
+                user: p.user.clone(),
+                priority: p.priority,
+                position: i + 1,
+            })
+            .collect()
+    }
+
+    pub fn cancel(&self, id: &str) -> Option<Result<()>> {
+        let info = {
+            let mut state = self.state.lock().unwrap();
+            let index = state.pending.iter().position(|p| p.id == id)?;
+            state.pending.remove(index)
+        };
+        Some(info.cancel())
+    }
+
+    pub fn cancel_all(&self) {
+        let pending = {
+            let mut state = self.state.lock().unwrap();
+            std::mem::take(&mut state.pending)
+        };
+        for info in pending.into_iter() {
+            if let Err(e) = info.cancel() {
+                let _ = term::print_error(&e.to_string());
+            }
+        }
+    }
+
+    pub fn workers(&self) -> Vec<WorkerInfo> {
+        let state = self.state.lock().unwrap();
+        state
+            .workers
+            .iter()
+            .map(|(id, w)| WorkerInfo {
+                id: id.to_string(),
+                labels: w.labels.clone(),
+                capacity: w.capacity,
+                running: w.runs.len(),
+            })
+            .collect()
+    }
+
+    pub fn register(
+        queue: &web::Data<Self>,
+        worker: &str,
+        labels: Vec<String>,
+        capacity: usize,
+        addr: Recipient<WorkerMessage>,
+    ) -> Result<()> {
+        {
+            let mut state = queue.state.lock().unwrap();
+            if state.workers.contains_key(worker) {
+                let message = format!("worker {} is already registered", worker);
+                return Err(BldError::Other(message));
+            }
+            let slot = WorkerSlot {
+                labels,
+                capacity,
+                addr,
+                runs: HashMap::new(),
+            };
+            state.workers.insert(worker.to_string(), slot);
+        }
+        PipelineQueue::dispatch(queue);
+        Ok(())
+    }
+
+    pub fn unregister(queue: &web::Data<Self>, worker: &str) {
+        let slot = {
+            let mut state = queue.state.lock().unwrap();
+            state.workers.remove(worker)
+        };
+        if let Some(slot) = slot {
+            for (_, info) in slot.runs.into_iter() {
+                info.fail(&BldError::Other(format!("worker {} disconnected", worker)));
+                info.complete(queue);
+            }
+        }
+        PipelineQueue::dispatch(queue);
+    }
+
+    pub fn receive(queue: &web::Data<Self>, worker: &str, message: WorkerMessage) -> Result<()> {
+        let id = match &message {
+            WorkerMessage::Log { id, .. }
+            | WorkerMessage::Checkout { id, .. }
+            | WorkerMessage::Trigger { id, .. }
+            | WorkerMessage::Summary { id, .. }
+            | WorkerMessage::State { id, .. } => id.clone(),
+            _ => return Ok(()),
+        };
+        let (ex, lg) = {
+            let state = queue.state.lock().unwrap();
+            match state.workers.get(worker).and_then(|s| s.runs.get(&id)) {
+                Some(info) => (info.ex.clone(), info.lg.clone()),
+                None => return Ok(()),
+            }
+        };
+        match message {
+            WorkerMessage::Log { entry, .. } => {
+                let mut lg = lg.lock().unwrap();
+                lg.step(entry.step.as_deref());
+                entry.replay(&mut *lg);
+            }
+            WorkerMessage::Checkout {
+                repository,
+                reference,
+                commit,
+                ..
+            } => {
+                let mut ex = ex.lock().unwrap();
+                ex.checkout(&repository, &reference, &commit)?;
+            }
+            WorkerMessage::Trigger {
+                pipeline,
+                variables,
+                ..
+            } => {
+                let mut ex = ex.lock().unwrap();
+                ex.trigger(&pipeline, variables)?;
+            }
+            WorkerMessage::Summary { summary, .. } => {
+                let mut ex = ex.lock().unwrap();
+                ex.summary(&summary)?;
+            }
+            WorkerMessage::State { state, .. } => {
+                let result = ex.lock().unwrap().update(state);
+                if let Err(e) = result {
+                    let mut lg = lg.lock().unwrap();
+                    lg.error(&format!("Couldn't persist the run: {}", e.to_string()));
+                }
+                if let RunState::Queued | RunState::Running = state {
+                    return Ok(());
+                }
+                let finished = {
+                    let mut state = queue.state.lock().unwrap();
+                    state
+                        .workers
+                        .get_mut(worker)
+                        .and_then(|s| s.runs.remove(&id))
+                };
+                if let Some(info) = finished {
+                    info.complete(queue);
+                    PipelineQueue::dispatch(queue);
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
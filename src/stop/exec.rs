@@ -16,8 +16,8 @@ pub fn exec(matches: &ArgMatches<'_>) -> Result<()> {
         None => (&srv.name, &srv.auth),
     };
     let sys = String::from("bld-stop");
-    let url = format!("http://{}:{}/stop", srv.host, srv.port);
+    let urls = srv.urls("/stop");
     let headers = headers(name, auth)?;
-    exec_post(sys, url, headers, id);
+    exec_post(sys, urls, headers, id);
     Ok(())
 }
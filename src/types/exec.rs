@@ -7,13 +7,21 @@ use std::collections::HashMap;
 pub struct ExecInfo {
     pub name: String,
     pub variables: Option<HashMap<String, String>>,
+    pub priority: Option<i64>,
+    pub files: Option<HashMap<String, String>>,
 }
 
 impl ExecInfo {
-    pub fn new(name: &str, variables: Option<HashMap<String, String>>) -> Self {
+    pub fn new(
+        name: &str,
+        variables: Option<HashMap<String, String>>,
+        priority: Option<i64>,
+    ) -> Self {
         Self {
             name: name.to_string(),
             variables,
+            priority,
+            files: None,
         }
     }
 }
@@ -0,0 +1,118 @@
+use crate::persist::{Logger, ShellLogger};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogStream {
+    Stdout,
+    Stderr,
+    System,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    Info,
+    Notice,
+    Error,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogEntry {
+    pub timestamp: Option<String>,
+    pub step: Option<String>,
+    pub stream: LogStream,
+    pub level: LogLevel,
+    pub text: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub offset: Option<u64>,
+}
+
+impl LogEntry {
+    pub fn new(step: Option<&str>, stream: LogStream, level: LogLevel, text: &str) -> Self {
+        Self {
+            timestamp: Some(chrono::Utc::now().to_rfc3339()),
+            step: step.map(|s| s.to_string()),
+            stream,
+            level,
+            text: text.to_string(),
+            offset: None,
+        }
+    }
+
+    pub fn plain(text: &str) -> Self {
+        Self {
+            timestamp: None,
+            step: None,
+            stream: LogStream::System,
+            level: LogLevel::Info,
+            text: text.to_string(),
+            offset: None,
+        }
+    }
+
+    pub fn parse(line: &str) -> Self {
+        match serde_json::from_str::<Self>(line) {
+            Ok(entry) => entry,
+            Err(_) => Self::plain(line),
+        }
+    }
+
+    pub fn render(&self, timestamps: bool, steps: bool) -> String {
+        let mut text = String::new();
+        if let (true, Some(timestamp)) = (timestamps, &self.timestamp) {
+            text.push_str(&format!("[{}] ", timestamp));
+        }
+        if let (true, Some(step)) = (steps, &self.step) {
+            text.push_str(&format!("[{}] ", step));
+        }
+        text.push_str(&self.text);
+        text
+    }
+
+    pub fn replay(&self, lg: &mut dyn Logger) {
+        self.write(lg, &self.text);
+    }
+
+    pub fn print(&self, timestamps: bool, steps: bool) {
+        self.write(&mut ShellLogger, &self.render(timestamps, steps));
+    }
+
+    fn write(&self, lg: &mut dyn Logger, text: &str) {
+        match (self.stream, self.level) {
+            (LogStream::Stdout, _) => lg.stdout(text),
+            (LogStream::Stderr, _) => lg.stderr(text),
+            (LogStream::System, LogLevel::Info) => lg.dumpln(text),
+            (LogStream::System, LogLevel::Notice) => lg.info(text),
+            (LogStream::System, LogLevel::Error) => lg.error(text),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_reads_json_entries() {
+        let entry = LogEntry::new(Some("build"), LogStream::Stderr, LogLevel::Error, "failed");
+        let line = serde_json::to_string(&entry).unwrap();
+        let parsed = LogEntry::parse(&line);
+        assert_eq!(parsed.step.as_deref(), Some("build"));
+        assert_eq!(parsed.stream, LogStream::Stderr);
+        assert_eq!(parsed.level, LogLevel::Error);
+        assert_eq!(parsed.text, "failed");
+    }
+
+    #[test]
+    fn parse_falls_back_to_plain_text() {
+        for line in ["plain text line", "{\"text\": \"incomplete\"", ""].iter() {
+            let parsed = LogEntry::parse(line);
+            assert_eq!(parsed.text, *line);
+            assert_eq!(parsed.timestamp, None);
+            assert_eq!(parsed.step, None);
+            assert_eq!(parsed.stream, LogStream::System);
+            assert_eq!(parsed.level, LogLevel::Info);
+        }
+    }
+}
@@ -1,17 +1,121 @@
 mod auth;
 mod exec;
+mod log;
 mod monit;
 mod push;
 mod recv;
+mod rerun;
 mod result;
+mod search;
+mod state;
+mod summary;
+mod worker;
 
 pub use auth::*;
 pub use exec::*;
+pub use log::*;
 pub use monit::*;
 pub use push::*;
 pub use recv::*;
+pub use rerun::*;
 pub use result::*;
+pub use search::*;
+pub use state::*;
+pub use summary::*;
+pub use worker::*;
 
+use std::collections::HashMap;
 use yaml_rust::Yaml;
 
 pub static EMPTY_YAML_VEC: Vec<Yaml> = Vec::new();
+
+pub fn yaml_string_map(yaml: &Yaml) -> HashMap<String, String> {
+    let mut map = HashMap::new();
+    if let Some(entries) = yaml.as_hash() {
+        for (key, value) in entries.iter() {
+            let value = match value {
+                Yaml::String(v) => Some(v.to_string()),
+                Yaml::Integer(v) => Some(v.to_string()),
+                Yaml::Real(v) => Some(v.to_string()),
+                Yaml::Boolean(v) => Some(v.to_string()),
+                _ => None,
+            };
+            if let (Some(key), Some(value)) = (key.as_str(), value) {
+                map.insert(key.to_string(), value);
+            }
+        }
+    }
+    map
+}
+
+fn split_unit(value: &str) -> Result<(u64, String)> {
+    let value = value.trim();
+    let index = value
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or_else(|| value.len());
+    let number = value[..index].parse::<u64>()?;
+    Ok((number, value[index..].trim().to_lowercase()))
+}
+
+pub fn parse_duration(value: &str) -> Result<u64> {
+    let (number, unit) = split_unit(value)?;
+    let multiplier = match &unit[..] {
+        "" | "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        "w" => 7 * 24 * 60 * 60,
+        _ => return Err(BldError::ParseError(format!("invalid duration {}", value))),
+    };
+    Ok(number * multiplier)
+}
+
+pub fn parse_size(value: &str) -> Result<u64> {
+    let (number, unit) = split_unit(value)?;
+    let multiplier = match &unit[..] {
+        "" | "b" => 1,
+        "kb" | "k" => 1024,
+        "mb" | "m" => 1024 * 1024,
+        "gb" | "g" => 1024 * 1024 * 1024,
+        _ => return Err(BldError::ParseError(format!("invalid size {}", value))),
+    };
+    Ok(number * multiplier)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_duration_reads_units() {
+        assert_eq!(parse_duration("30").ok(), Some(30));
+        assert_eq!(parse_duration("30s").ok(), Some(30));
+        assert_eq!(parse_duration("5m").ok(), Some(300));
+        assert_eq!(parse_duration("2h").ok(), Some(7200));
+        assert_eq!(parse_duration(" 7D ").ok(), Some(604_800));
+        assert_eq!(parse_duration("1w").ok(), Some(604_800));
+    }
+
+    #[test]
+    fn parse_duration_rejects_invalid_values() {
+        assert!(parse_duration("5y").is_err());
+        assert!(parse_duration("m").is_err());
+        assert!(parse_duration("").is_err());
+        assert!(parse_duration("-5m").is_err());
+    }
+
+    #[test]
+    fn parse_size_reads_units() {
+        assert_eq!(parse_size("512").ok(), Some(512));
+        assert_eq!(parse_size("512b").ok(), Some(512));
+        assert_eq!(parse_size("10k").ok(), Some(10 * 1024));
+        assert_eq!(parse_size("10 MB").ok(), Some(10 * 1024 * 1024));
+        assert_eq!(parse_size("2g").ok(), Some(2 * 1024 * 1024 * 1024));
+    }
+
+    #[test]
+    fn parse_size_rejects_invalid_values() {
+        assert!(parse_size("10tb").is_err());
+        assert!(parse_size("mb").is_err());
+    }
+}
@@ -1,16 +1,23 @@
 use actix::Message;
 use serde::{Deserialize, Serialize};
 
-#[derive(Default, Serialize, Deserialize, Message)]
+#[derive(Default, Clone, Serialize, Deserialize, Message)]
 #[rtype(result = "()")]
 pub struct MonitInfo {
     pub id: Option<String>,
     pub name: Option<String>,
     pub last: bool,
+    #[serde(rename = "from-offset", default)]
+    pub from_offset: Option<u64>,
 }
 
 impl MonitInfo {
     pub fn new(id: Option<String>, name: Option<String>, last: bool) -> Self {
-        Self { id, name, last }
+        Self {
+            id,
+            name,
+            last,
+            from_offset: None,
+        }
     }
 }
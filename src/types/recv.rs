@@ -1,19 +1,32 @@
 use crate::types::{BldError, Result};
-use std::sync::mpsc::Receiver;
-use std::sync::{Arc, Mutex};
+use futures::future;
+use tokio::sync::watch::Receiver;
+
+pub type StopReceiver = Receiver<bool>;
 
 pub trait CheckStopSignal {
     fn check_stop_signal(&self) -> Result<()>;
 }
 
-impl CheckStopSignal for Option<Arc<Mutex<Receiver<bool>>>> {
+impl CheckStopSignal for Option<StopReceiver> {
     fn check_stop_signal(&self) -> Result<()> {
         if let Some(comm) = &self {
-            let comm = comm.lock().unwrap();
-            if let Ok(true) = comm.try_recv() {
-                return Err(BldError::Other("stop signal sent to thread".to_string()));
+            if *comm.borrow() {
+                return Err(BldError::Stopped);
             }
         }
         Ok(())
     }
 }
+
+pub async fn stop_signal(cm: &Option<StopReceiver>) {
+    if let Some(comm) = cm {
+        let mut comm = comm.clone();
+        while let Some(stop) = comm.recv().await {
+            if stop {
+                return;
+            }
+        }
+    }
+    future::pending::<()>().await
+}
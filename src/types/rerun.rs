@@ -0,0 +1,16 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RerunInfo {
+    pub id: String,
+    pub latest_pipeline: bool,
+}
+
+impl RerunInfo {
+    pub fn new(id: &str, latest_pipeline: bool) -> Self {
+        Self {
+            id: id.to_string(),
+            latest_pipeline,
+        }
+    }
+}
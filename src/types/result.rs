@@ -8,10 +8,12 @@ use oauth2::basic::BasicErrorResponseType;
 use oauth2::reqwest::Error as ReqError;
 use oauth2::url::ParseError;
 use oauth2::{RequestTokenError, StandardErrorResponse};
+use regex::Error as RegexError;
 use std::convert::From;
 use std::error::Error;
 use std::io;
 use std::marker::{Send, Sync};
+use std::num::ParseIntError;
 use std::str::ParseBoolError;
 use yaml_rust::scanner::ScanError;
 
@@ -26,6 +28,7 @@ pub enum BldError {
     ShipliftError(String),
     YamlError(String),
     OAuth2(String),
+    Stopped,
     Other(String),
 }
 
@@ -130,6 +133,18 @@ impl From<ParseBoolError> for BldError {
     }
 }
 
+impl From<ParseIntError> for BldError {
+    fn from(error: ParseIntError) -> Self {
+        Self::ParseError(error.to_string())
+    }
+}
+
+impl From<RegexError> for BldError {
+    fn from(error: RegexError) -> Self {
+        Self::ParseError(error.to_string())
+    }
+}
+
 impl std::string::ToString for BldError {
     fn to_string(&self) -> String {
         match self {
@@ -141,6 +156,7 @@ impl std::string::ToString for BldError {
             Self::ShipliftError(s) => s.to_string(),
             Self::YamlError(y) => y.to_string(),
             Self::OAuth2(o) => o.to_string(),
+            Self::Stopped => String::from("stop signal sent to thread"),
             Self::Other(o) => o.to_string(),
         }
     }
@@ -0,0 +1,47 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SearchInfo {
+    pub pattern: String,
+    pub pipeline: Option<String>,
+    pub since: Option<String>,
+    pub context: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SearchMatch {
+    pub id: String,
+    pub pipeline: String,
+    pub line: i64,
+    pub step: Option<String>,
+    pub before: Vec<String>,
+    pub text: String,
+    pub after: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SearchResults {
+    pub matches: Vec<SearchMatch>,
+    pub truncated: bool,
+}
+
+impl ToString for SearchMatch {
+    fn to_string(&self) -> String {
+        let mut info = String::new();
+        info.push_str(&format!("ID: {}\n", self.id));
+        info.push_str(&format!("PIPELINE: {}\n", self.pipeline));
+        if let Some(step) = &self.step {
+            info.push_str(&format!("STEP: {}\n", step));
+        }
+        info.push_str(&format!("LINE: {}\n", self.line));
+        let first = self.line - self.before.len() as i64;
+        for (i, text) in self.before.iter().enumerate() {
+            info.push_str(&format!("  {}: {}\n", first + i as i64, text));
+        }
+        info.push_str(&format!("> {}: {}", self.line, self.text));
+        for (i, text) in self.after.iter().enumerate() {
+            info.push_str(&format!("\n  {}: {}", self.line + 1 + i as i64, text));
+        }
+        info
+    }
+}
@@ -0,0 +1,78 @@
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display, Formatter};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RunState {
+    Queued,
+    Running,
+    Finished,
+    Failed,
+    Stopped,
+    Cancelled,
+    Interrupted,
+}
+
+impl RunState {
+    pub fn is_running(self) -> bool {
+        self == Self::Running
+    }
+}
+
+impl Display for RunState {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Queued => write!(f, "queued"),
+            Self::Running => write!(f, "running"),
+            Self::Finished => write!(f, "finished"),
+            Self::Failed => write!(f, "failed"),
+            Self::Stopped => write!(f, "stopped"),
+            Self::Cancelled => write!(f, "cancelled"),
+            Self::Interrupted => write!(f, "interrupted"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RunTrigger {
+    Manual,
+    Schedule,
+    Webhook,
+    Upstream(String, usize),
+    Rerun(String),
+}
+
+impl RunTrigger {
+    pub fn upstream(&self) -> Option<&str> {
+        match self {
+            Self::Upstream(id, _) => Some(id),
+            _ => None,
+        }
+    }
+
+    pub fn depth(&self) -> usize {
+        match self {
+            Self::Upstream(_, depth) => *depth,
+            _ => 0,
+        }
+    }
+
+    pub fn rerun_of(&self) -> Option<&str> {
+        match self {
+            Self::Rerun(id) => Some(id),
+            _ => None,
+        }
+    }
+}
+
+impl Display for RunTrigger {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Manual => write!(f, "manual"),
+            Self::Schedule => write!(f, "schedule"),
+            Self::Webhook => write!(f, "webhook"),
+            Self::Upstream(_, _) => write!(f, "upstream"),
+            Self::Rerun(_) => write!(f, "rerun"),
+        }
+    }
+}
@@ -0,0 +1,88 @@
+use crate::types::{BldError, Result, RunState};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct StepSummary {
+    pub name: String,
+    pub state: String,
+    pub duration: u64,
+    pub image_pull: u64,
+    pub artifacts: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct RunSummary {
+    pub steps: Vec<StepSummary>,
+}
+
+fn seconds(millis: u64) -> String {
+    format!("{:.1}s", millis as f64 / 1000.0)
+}
+
+impl RunSummary {
+    pub fn push(
+        &mut self,
+        name: &str,
+        result: &Result<()>,
+        duration: Duration,
+        image_pull: Duration,
+        artifacts: Duration,
+    ) {
+        let state = match result {
+            Ok(_) => RunState::Finished,
+            Err(BldError::Stopped) => RunState::Stopped,
+            Err(_) => RunState::Failed,
+        };
+        self.steps.push(StepSummary {
+            name: name.to_string(),
+            state: state.to_string(),
+            duration: duration.as_millis() as u64,
+            image_pull: image_pull.as_millis() as u64,
+            artifacts: artifacts.as_millis() as u64,
+        });
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    pub fn table(&self) -> String {
+        let width = self
+            .steps
+            .iter()
+            .map(|s| s.name.len())
+            .chain(std::iter::once(5))
+            .max()
+            .unwrap_or(5);
+        let row = |name: &str, state: &str, duration: &str, pull: &str, artifacts: &str| {
+            format!(
+                "{:<width$}  {:<11}  {:>10}  {:>10}  {:>10}",
+                name,
+                state,
+                duration,
+                pull,
+                artifacts,
+                width = width
+            )
+        };
+        let mut lines = vec![row("STEP", "STATUS", "DURATION", "IMAGE PULL", "ARTIFACTS")];
+        for step in self.steps.iter() {
+            lines.push(row(
+                &step.name,
+                &step.state,
+                &seconds(step.duration),
+                &seconds(step.image_pull),
+                &seconds(step.artifacts),
+            ));
+        }
+        lines.push(row(
+            "TOTAL",
+            "",
+            &seconds(self.steps.iter().map(|s| s.duration).sum()),
+            &seconds(self.steps.iter().map(|s| s.image_pull).sum()),
+            &seconds(self.steps.iter().map(|s| s.artifacts).sum()),
+        ));
+        lines.join("\n")
+    }
+}
@@ -0,0 +1,46 @@
+use crate::types::{LogEntry, RunState, RunSummary};
+use actix::Message;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Serialize, Deserialize, Debug, Message)]
+#[rtype(result = "()")]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum WorkerMessage {
+    Register {
+        labels: Vec<String>,
+        capacity: usize,
+    },
+    Run {
+        id: String,
+        name: String,
+        variables: HashMap<String, String>,
+        files: HashMap<String, String>,
+    },
+    Stop {
+        id: String,
+    },
+    Log {
+        id: String,
+        entry: LogEntry,
+    },
+    State {
+        id: String,
+        state: RunState,
+    },
+    Checkout {
+        id: String,
+        repository: String,
+        reference: String,
+        commit: String,
+    },
+    Trigger {
+        id: String,
+        pipeline: String,
+        variables: HashMap<String, String>,
+    },
+    Summary {
+        id: String,
+        summary: RunSummary,
+    },
+}
@@ -0,0 +1,25 @@
+use crate::config::definitions::VERSION;
+use clap::{App, Arg, SubCommand};
+
+pub fn command() -> App<'static, 'static> {
+    let server = Arg::with_name("server")
+        .short("s")
+        .long("server")
+        .help("The name of the server to receive runs from")
+        .takes_value(true);
+    let labels = Arg::with_name("labels")
+        .short("l")
+        .long("labels")
+        .help("The labels advertised by the worker, used to match the runs-on value and labels of a pipeline")
+        .multiple(true)
+        .takes_value(true);
+    let capacity = Arg::with_name("capacity")
+        .short("c")
+        .long("capacity")
+        .help("The number of runs that the worker can execute at the same time")
+        .takes_value(true);
+    SubCommand::with_name("worker")
+        .about("Connects to a bld server and executes the runs that it dispatches")
+        .version(VERSION)
+        .args(&[server, labels, capacity])
+}
@@ -0,0 +1,174 @@
+use crate::helpers::term::print_error;
+use crate::persist::{Execution, Logger};
+use crate::run::Runner;
+use crate::types::{BldError, RunState, WorkerMessage};
+use crate::worker::{WorkerExec, WorkerLogger};
+use actix::io::{SinkWrite, WriteHandler};
+use actix::{Actor, ActorContext, AsyncContext, Context, Handler, StreamHandler};
+use actix_codec::Framed;
+use awc::error::WsProtocolError;
+use awc::ws::{Codec, Frame, Message};
+use awc::BoxedSocket;
+use bytes::Bytes;
+use futures::stream::SplitSink;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::runtime::Handle;
+use tokio::sync::oneshot;
+use tokio::sync::watch::{self, Sender};
+
+pub struct WorkerClient {
+    writer: SinkWrite<Message, SplitSink<Framed<BoxedSocket, Codec>, Message>>,
+    runtime: Handle,
+    labels: Vec<String>,
+    capacity: usize,
+    senders: Arc<Mutex<HashMap<String, Sender<bool>>>>,
+    closed: Option<oneshot::Sender<()>>,
+}
+
+impl WorkerClient {
+    pub fn new(
+        writer: SinkWrite<Message, SplitSink<Framed<BoxedSocket, Codec>, Message>>,
+        runtime: Handle,
+        labels: Vec<String>,
+        capacity: usize,
+        closed: oneshot::Sender<()>,
+    ) -> Self {
+        Self {
+            writer,
+            runtime,
+            labels,
+            capacity,
+            senders: Arc::new(Mutex::new(HashMap::new())),
+            closed: Some(closed),
+        }
+    }
+
+    fn heartbeat(&mut self, ctx: &mut Context<Self>) {
+        ctx.run_interval(Duration::new(1, 0), |act, _ctx| {
+            let _ = act.writer.write(Message::Ping(Bytes::from_static(b"")));
+        });
+    }
+
+    fn write(&mut self, message: &WorkerMessage) {
+        if let Ok(message) = serde_json::to_string(message) {
+            let _ = self.writer.write(Message::Text(message));
+        }
+    }
+
+    fn run(
+        &self,
+        ctx: &mut Context<Self>,
+        id: String,
+        name: String,
+        variables: HashMap<String, String>,
+        files: HashMap<String, String>,
+    ) {
+        println!("Starting run {} of pipeline {}", id, name);
+        let (tx, rx) = watch::channel(false);
+        {
+            let mut senders = self.senders.lock().unwrap();
+            senders.insert(id.clone(), tx);
+        }
+        let addr = ctx.address().recipient();
+        let senders = self.senders.clone();
+        self.runtime.spawn(async move {
+            let ex = WorkerExec::atom(&id, addr.clone());
+            let lg = WorkerLogger::atom(&id, addr);
+            let result = Runner::from_file(
+                name,
+                ex.clone(),
+                lg.clone(),
+                Some(rx),
+                Arc::new(variables),
+                Some(Arc::new(files)),
+            )
+            .await;
+            match result {
+                Ok(_) | Err(BldError::Stopped) => {}
+                Err(e) => {
+                    lg.lock().unwrap().dumpln(&e.to_string());
+                    let _ = ex.lock().unwrap().update(RunState::Failed);
+                }
+            }
+            let mut senders = senders.lock().unwrap();
+            senders.remove(&id);
+            println!("Finished run {}", id);
+        });
+    }
+
+    fn stop(&self, id: &str) {
+        let senders = self.senders.lock().unwrap();
+        if let Some(sender) = senders.get(id) {
+            let _ = sender.broadcast(true);
+        }
+    }
+
+    fn receive(&mut self, ctx: &mut Context<Self>, text: &str) {
+        match serde_json::from_str::<WorkerMessage>(text) {
+            Ok(WorkerMessage::Run {
+                id,
+                name,
+                variables,
+                files,
+            }) => self.run(ctx, id, name, variables, files),
+            Ok(WorkerMessage::Stop { id }) => self.stop(&id),
+            Ok(_) => {}
+            Err(e) => {
+                let _ = print_error(&e.to_string());
+            }
+        }
+    }
+}
+
+impl Actor for WorkerClient {
+    type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Context<Self>) {
+        let register = WorkerMessage::Register {
+            labels: self.labels.clone(),
+            capacity: self.capacity,
+        };
+        self.write(&register);
+        self.heartbeat(ctx);
+    }
+
+    fn stopped(&mut self, _: &mut Context<Self>) {
+        let senders = self.senders.lock().unwrap();
+        for (id, sender) in senders.iter() {
+            println!("Stopping run {} since the server connection was lost", id);
+            let _ = sender.broadcast(true);
+        }
+        if let Some(closed) = self.closed.take() {
+            let _ = closed.send(());
+        }
+    }
+}
+
+impl Handler<WorkerMessage> for WorkerClient {
+    type Result = ();
+
+    fn handle(&mut self, msg: WorkerMessage, _ctx: &mut Self::Context) {
+        self.write(&msg);
+    }
+}
+
+impl StreamHandler<Result<Frame, WsProtocolError>> for WorkerClient {
+    fn handle(&mut self, msg: Result<Frame, WsProtocolError>, ctx: &mut Context<Self>) {
+        match msg {
+            Ok(Frame::Text(bt)) => self.receive(ctx, &String::from_utf8_lossy(&bt[..])),
+            Ok(Frame::Ping(msg)) => {
+                let _ = self.writer.write(Message::Pong(msg));
+            }
+            Ok(Frame::Close(_)) => ctx.stop(),
+            _ => {}
+        }
+    }
+
+    fn finished(&mut self, ctx: &mut Context<Self>) {
+        ctx.stop();
+    }
+}
+
+impl WriteHandler<WsProtocolError> for WorkerClient {}
@@ -0,0 +1,102 @@
+use crate::config::definitions::{WORKER_RECONNECT_MAX_DELAY, WORKER_RECONNECT_MIN_DELAY};
+use crate::config::BldConfig;
+use crate::helpers::errors::auth_for_server_invalid;
+use crate::helpers::request::{headers, ws_connect};
+use crate::helpers::term::{print_error, print_info};
+use crate::types::Result;
+use crate::worker::WorkerClient;
+use actix::{io::SinkWrite, Actor, Arbiter, StreamHandler, System};
+use clap::ArgMatches;
+use futures::stream::StreamExt;
+use std::collections::HashMap;
+use std::time::Duration;
+use tokio::runtime::{Builder, Handle};
+use tokio::sync::oneshot;
+
+struct WorkerConnectionInfo {
+    urls: Vec<String>,
+    headers: HashMap<String, String>,
+    labels: Vec<String>,
+    capacity: usize,
+}
+
+async fn remote_invoke(
+    info: &WorkerConnectionInfo,
+    runtime: Handle,
+) -> Result<oneshot::Receiver<()>> {
+    let (url, framed) = ws_connect(info.urls.clone(), &info.headers).await?;
+    let (sink, stream) = framed.split();
+    print_info(&format!(
+        "worker connected to {} with labels: {}",
+        url,
+        info.labels.join(", ")
+    ))?;
+    let (tx, rx) = oneshot::channel();
+    WorkerClient::create(|ctx| {
+        WorkerClient::add_stream(stream, ctx);
+        WorkerClient::new(
+            SinkWrite::new(sink, ctx),
+            runtime,
+            info.labels.clone(),
+            info.capacity,
+            tx,
+        )
+    });
+    Ok(rx)
+}
+
+async fn remote_loop(info: WorkerConnectionInfo, runtime: Handle) {
+    let mut delay = WORKER_RECONNECT_MIN_DELAY;
+    loop {
+        match remote_invoke(&info, runtime.clone()).await {
+            Ok(closed) => {
+                let _ = closed.await;
+                let _ = print_error("worker lost the connection to the server");
+                delay = WORKER_RECONNECT_MIN_DELAY;
+            }
+            Err(e) => {
+                let _ = print_error(&e.to_string());
+            }
+        }
+        let _ = print_info(&format!("reconnecting in {} seconds", delay));
+        tokio::time::delay_for(Duration::from_secs(delay)).await;
+        delay = (delay * 2).min(WORKER_RECONNECT_MAX_DELAY);
+    }
+}
+
+pub fn exec(matches: &ArgMatches<'_>) -> Result<()> {
+    let config = BldConfig::load()?;
+    let labels = matches
+        .values_of("labels")
+        .map(|labels| labels.map(|l| l.to_string()).collect())
+        .unwrap_or_else(|| vec!["machine".to_string(), "docker".to_string()]);
+    let capacity = match matches.value_of("capacity") {
+        Some(capacity) => capacity.parse::<usize>()?,
+        None => config.local.max_concurrent_runs,
+    };
+    let srv = config.remote.server_or_first(matches.value_of("server"))?;
+    let (name, auth) = match &srv.same_auth_as {
+        Some(name) => match config.remote.servers.iter().find(|s| &s.name == name) {
+            Some(srv) => (&srv.name, &srv.auth),
+            None => return auth_for_server_invalid(),
+        },
+        None => (&srv.name, &srv.auth),
+    };
+    let info = WorkerConnectionInfo {
+        urls: srv.urls("/ws-worker"),
+        headers: headers(name, auth)?,
+        labels,
+        capacity,
+    };
+
+    let runtime = Builder::new()
+        .threaded_scheduler()
+        .enable_all()
+        .thread_name("bld-worker")
+        .build()?;
+    let handle = runtime.handle().clone();
+    let system = System::new("bld-worker");
+    Arbiter::spawn(remote_loop(info, handle));
+    let _ = system.run();
+    Ok(())
+}
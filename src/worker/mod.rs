@@ -0,0 +1,9 @@
+mod cli;
+mod client;
+mod exec;
+mod remote;
+
+pub use cli::*;
+pub use client::*;
+pub use exec::*;
+pub use remote::*;
@@ -0,0 +1,109 @@
+use crate::persist::{Execution, Logger};
+use crate::types::{LogEntry, LogLevel, LogStream, Result, RunState, RunSummary, WorkerMessage};
+use actix::Recipient;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+pub struct WorkerLogger {
+    id: String,
+    step: Option<String>,
+    addr: Recipient<WorkerMessage>,
+}
+
+impl WorkerLogger {
+    pub fn atom(id: &str, addr: Recipient<WorkerMessage>) -> Arc<Mutex<Self>> {
+        Arc::new(Mutex::new(Self {
+            id: id.to_string(),
+            step: None,
+            addr,
+        }))
+    }
+
+    fn send(&self, stream: LogStream, level: LogLevel, text: &str) {
+        let _ = self.addr.do_send(WorkerMessage::Log {
+            id: self.id.clone(),
+            entry: LogEntry::new(self.step.as_deref(), stream, level, text),
+        });
+    }
+}
+
+impl Logger for WorkerLogger {
+    fn step(&mut self, name: Option<&str>) {
+        self.step = name.map(|n| n.to_string());
+    }
+
+    fn dumpln(&mut self, text: &str) {
+        self.send(LogStream::System, LogLevel::Info, text);
+    }
+
+    fn stdout(&mut self, line: &str) {
+        self.send(LogStream::Stdout, LogLevel::Info, line);
+    }
+
+    fn stderr(&mut self, line: &str) {
+        self.send(LogStream::Stderr, LogLevel::Info, line);
+    }
+
+    fn info(&mut self, text: &str) {
+        self.send(LogStream::System, LogLevel::Notice, text);
+    }
+
+    fn error(&mut self, text: &str) {
+        self.send(LogStream::System, LogLevel::Error, text);
+    }
+}
+
+pub struct WorkerExec {
+    id: String,
+    addr: Recipient<WorkerMessage>,
+}
+
+impl WorkerExec {
+    pub fn atom(id: &str, addr: Recipient<WorkerMessage>) -> Arc<Mutex<Self>> {
+        Arc::new(Mutex::new(Self {
+            id: id.to_string(),
+            addr,
+        }))
+    }
+}
+
+impl Execution for WorkerExec {
+    fn id(&self) -> Option<String> {
+        Some(self.id.clone())
+    }
+
+    fn update(&mut self, state: RunState) -> Result<()> {
+        let _ = self.addr.do_send(WorkerMessage::State {
+            id: self.id.clone(),
+            state,
+        });
+        Ok(())
+    }
+
+    fn checkout(&mut self, repository: &str, reference: &str, commit: &str) -> Result<()> {
+        let _ = self.addr.do_send(WorkerMessage::Checkout {
+            id: self.id.clone(),
+            repository: repository.to_string(),
+            reference: reference.to_string(),
+            commit: commit.to_string(),
+        });
+        Ok(())
+    }
+
+    fn trigger(&mut self, pipeline: &str, variables: HashMap<String, String>) -> Result<()> {
+        let _ = self.addr.do_send(WorkerMessage::Trigger {
+            id: self.id.clone(),
+            pipeline: pipeline.to_string(),
+            variables,
+        });
+        Ok(())
+    }
+
+    fn summary(&mut self, summary: &RunSummary) -> Result<()> {
+        let _ = self.addr.do_send(WorkerMessage::Summary {
+            id: self.id.clone(),
+            summary: summary.clone(),
+        });
+        Ok(())
+    }
+}